@@ -0,0 +1,58 @@
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use poksen::{
+    CU,
+    game::{Cards, DeckKind, Game, RNG, best_of, equity},
+    lobby::{Lobby, Seat},
+};
+use rand::SeedableRng;
+
+fn seats(n: usize) -> Vec<Seat> {
+    let mut builder = Lobby::builder();
+    builder.add_cpu_players(n, CU!(5000)).unwrap();
+    builder.players
+}
+
+fn bench_showdown(c: &mut Criterion) {
+    let seats = seats(9);
+    let game = Game::buid_with_seed(&seats, 0, [1; 32]).unwrap();
+    c.bench_function("showdown 9 players", |b| {
+        b.iter(|| {
+            let mut game = game.clone();
+            black_box(game.check_down_to_showdown().unwrap())
+        })
+    });
+}
+
+fn bench_equity(c: &mut Criterion) {
+    let hand = ["As".parse().unwrap(), "Kd".parse().unwrap()];
+    let mut rng = RNG::from_seed([2; 32]);
+    c.bench_function("equity estimate 1000 iterations", |b| {
+        b.iter(|| {
+            black_box(equity::estimate(hand, &[], 3, DeckKind::default(), 1000, &mut rng).unwrap())
+        })
+    });
+}
+
+fn bench_best_of(c: &mut Criterion) {
+    let game = Game::buid_with_seed(&seats(9), 0, [3; 32]).unwrap();
+    let mut deck = game.deck().to_vec();
+    let board: Vec<_> = (0..5).map(|_| deck.pop().unwrap()).collect();
+    let hands: Vec<Cards<7>> = game
+        .players()
+        .iter()
+        .map(|player| {
+            let hand = player.hand();
+            [
+                hand[0], hand[1], board[0], board[1], board[2], board[3], board[4],
+            ]
+        })
+        .collect();
+    c.bench_function("best of 9 hands", |b| {
+        b.iter(|| black_box(best_of(black_box(&hands))))
+    });
+}
+
+criterion_group!(benches, bench_showdown, bench_equity, bench_best_of);
+criterion_main!(benches);
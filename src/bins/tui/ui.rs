@@ -1,7 +1,7 @@
 use color_eyre::Result;
 use crossterm::event::{Event, KeyCode, KeyModifiers};
 use poksen::{
-    CU,
+    CU, PoksError,
     currency::Currency,
     game::{Action, PlayerID},
     lobby::Lobby,
@@ -11,6 +11,9 @@ use tracing::{debug, info, trace};
 
 mod render;
 
+/// How many ticks of the game are made per frame at most
+const FAST_FORWARD_STEPS: usize = 64;
+
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub(crate) enum InputMode {
     #[default]
@@ -34,9 +37,9 @@ impl PoksTUI {
         let mut lobby_builder = Lobby::builder();
 
         trace!("Adding Local Player");
-        let player = Box::new(PlayerLocal::new());
-        let player_action_field = player.action_field_reference();
-        lobby_builder.add_player(player).unwrap();
+        lobby_builder
+            .add_local_player()
+            .expect("could not add local player");
 
         trace!("Adding CPU Players");
         for _ in 1..8 {
@@ -51,13 +54,15 @@ impl PoksTUI {
         }
 
         trace!("Building datastructure");
+        let world = lobby_builder.build().expect("could not prepare world");
+        let (player_id, player_action_field) = world.local_players()[0].clone();
         let ui = Self {
-            world: lobby_builder.build().expect("could not prepare world"),
+            world,
             should_exit: false,
             frame: 0,
             message: None,
             player_af: player_action_field,
-            player_id: 0,
+            player_id,
             bet: None,
             input_mode: Default::default(),
         };
@@ -74,7 +79,8 @@ impl PoksTUI {
         if self.lobby().game.is_finished() {
             self.message = Some("Game finished. Press F6 or Space for a new game.".to_string());
         } else {
-            self.world.tick_game()?;
+            // the computer players act right away, the TUI waits for the local player
+            self.world.fast_forward(FAST_FORWARD_STEPS)?;
         }
 
         Ok(())
@@ -169,10 +175,15 @@ impl PoksTUI {
                     *bet -= self.world.game.big_blind();
                 }
                 KeyCode::Enter => {
-                    PlayerLocal::set_action(
-                        &self.player_af,
-                        Action::Raise(self.bet.take().unwrap()),
-                    );
+                    let amount = self.bet.unwrap_or_default();
+                    let raise = Action::Raise(amount);
+                    if !self.lobby().game.player_can_afford(self.player_id, raise) {
+                        self.message = Some(format!("You can not afford a raise of {amount}"));
+                        return Ok(());
+                    }
+                    self.message = None;
+                    self.bet = None;
+                    PlayerLocal::set_action(&self.player_af, raise);
                     self.set_input_mode(InputMode::Normal);
                 }
                 _ => (),
@@ -187,8 +198,11 @@ impl PoksTUI {
 
     pub(crate) fn start_new_game(&mut self) {
         self.message = None;
-        self.world
-            .start_new_game()
-            .expect("could not start new game");
+        match self.world.start_new_game() {
+            Err(PoksError::SessionOver { winner }) => {
+                self.message = Some(format!("Player {winner} has won the session!"))
+            }
+            res => res.expect("could not start new game"),
+        }
     }
 }
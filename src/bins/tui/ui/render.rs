@@ -1,4 +1,4 @@
-use poksen::game::evaluator;
+use poksen::game::Game;
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, Paragraph, Wrap},
@@ -40,7 +40,11 @@ impl PoksTUI {
     }
 
     fn metadata(&self) -> String {
-        let mut buf = format!("Frame: {}", self.frame);
+        let mut buf = format!(
+            "Frame: {} | Seed: {}",
+            self.frame,
+            Game::seed_to_hex(&self.lobby().game.current_seed())
+        );
         if self.message.is_some() {
             let add = format!(" | Message: {}", self.message.as_ref().unwrap());
             buf.push_str(&add);
@@ -70,12 +74,7 @@ impl PoksTUI {
             player.currency(),
         );
 
-        if player.hand().is_some() && game.community_cards().len() >= 3 {
-            let combined = game.hand_plus_table(self.player_id);
-
-            let eval = evaluator()
-                .evaluate_five(&*combined)
-                .expect("could not evaluate player hand + community cards");
+        if let Some(eval) = game.best_eval(self.player_id) {
             buf.push_str(&format!(" | Evaluation: {eval}"));
         }
 
@@ -83,28 +82,27 @@ impl PoksTUI {
     }
 
     fn render_players(&self, area: Rect, frame: &mut Frame<'_>) {
-        let players = self.lobby().players();
+        let seats = self.lobby().seat_summaries();
         let layout = Layout::default()
             .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Length(4); players.len()])
+            .constraints(vec![Constraint::Length(4); seats.len()])
             .split(area);
 
-        for (idx, (player, layout)) in players.iter().zip(layout.iter()).enumerate() {
+        for (idx, (seat, layout)) in seats.iter().zip(layout.iter()).enumerate() {
             frame.render_widget(
                 Paragraph::new(format!(
                     "  Currency: {}\n  Total Bet: {}",
-                    player.currency(),
-                    self.lobby().game.players()[idx].total_bet()
+                    seat.currency, seat.total_bet
                 ))
                 .block(Block::new().borders(Borders::ALL).title({
                     let mut sbuf = format!(" Player {idx}");
-                    if idx == self.lobby().game.big_blind_position() {
+                    if seat.is_bb {
                         sbuf.push_str(" (BB)");
                     }
-                    if idx == self.lobby().game.small_blind_position() {
+                    if seat.is_sb {
                         sbuf.push_str(" (SB)");
                     }
-                    if idx == self.lobby().game.dealer_position() {
+                    if seat.is_dealer {
                         sbuf.push_str(" (D)");
                     }
                     sbuf.push(' ');
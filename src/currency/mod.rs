@@ -5,11 +5,57 @@ use std::{
         Add, AddAssign, Deref, DerefMut, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub,
         SubAssign,
     },
+    str::FromStr,
 };
 
+use crate::errors::PoksError;
+
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Currency(u64);
 
+/// How [`Currency::format`] writes an amount
+///
+/// The default is the format of [`Display`], like `1.234,56ŧ`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct FormatOpts {
+    /// Append [`Currency::CURRENCY_SYMBOL`]
+    pub symbol: bool,
+    /// Group the credits by thousands with this character
+    pub thousands_separator: Option<char>,
+    pub decimal_separator: char,
+    /// Write the cents, otherwise the amount is rounded to whole credits
+    pub cents: bool,
+}
+
+impl FormatOpts {
+    /// Like `1,234.56`
+    pub const US: FormatOpts = FormatOpts {
+        symbol: false,
+        thousands_separator: Some(','),
+        decimal_separator: '.',
+        cents: true,
+    };
+    /// Like `1234.56`, for machines rather than people, for example in CSV files
+    pub const PLAIN: FormatOpts = FormatOpts {
+        symbol: false,
+        thousands_separator: None,
+        decimal_separator: '.',
+        cents: true,
+    };
+}
+
+impl Default for FormatOpts {
+    fn default() -> Self {
+        Self {
+            symbol: true,
+            thousands_separator: Some(Currency::THOUSANDS_SEPARATOR),
+            decimal_separator: Currency::DECIMAL_SEPARATOR,
+            cents: true,
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! CU {
     ($cr:tt) => {
@@ -59,9 +105,152 @@ impl Currency {
         }
     }
 
+    /// Write this amount in another format than [`Display`] does
+    pub fn format(&self, opts: FormatOpts) -> String {
+        let amount = if opts.cents {
+            *self
+        } else {
+            self.round_cents()
+        };
+        let credits = amount.credits().to_string();
+        let mut buf = String::with_capacity(credits.len() * 4 / 3 + 4);
+        for (i, ch) in credits.chars().enumerate() {
+            if i > 0 && (credits.len() - i) % 3 == 0 {
+                buf.extend(opts.thousands_separator);
+            }
+            buf.push(ch);
+        }
+        if opts.cents {
+            buf.push(opts.decimal_separator);
+            buf.push_str(&format!("{:02}", amount.cents()));
+        }
+        if opts.symbol {
+            buf.push(Self::CURRENCY_SYMBOL);
+        }
+        buf
+    }
+
     pub const fn as_float(&self) -> f64 {
         self.0 as f64 / 100.0
     }
+
+    /// Convert an amount in credits to [`Currency`], rounding to the nearest cent
+    ///
+    /// Unlike [`try_from_float`](Self::try_from_float), this does not fail: NaN and negative
+    /// amounts become zero and amounts that are too large saturate, like an `as` cast would.
+    pub fn from_float(f: f64) -> Self {
+        match Self::try_from_float(f) {
+            Ok(amount) => amount,
+            Err(_) if f > 0.0 => Self(u64::MAX),
+            Err(_) => Self::ZERO,
+        }
+    }
+
+    /// Convert an amount in credits to [`Currency`], rounding to the nearest cent
+    ///
+    /// Halves are rounded up, not to the even cent. The rounding is done on the shortest
+    /// decimal representation of `f`, so `1.005` becomes `1,01ŧ`, even though the closest
+    /// [`f64`] is slightly less than that.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::CurrencyParseError`] if `f` is NaN, infinite or negative, and
+    /// [`PoksError::CurrencyOverflow`] if it is too large.
+    pub fn try_from_float(f: f64) -> Result<Self, PoksError> {
+        let err = |reason: &str| PoksError::currency_parse(f.to_string(), reason);
+        if !f.is_finite() {
+            return Err(err("not a finite number"));
+        }
+        if f < 0.0 {
+            return Err(err("negative amounts are not supported"));
+        }
+        // `abs` turns -0.0 into 0.0, Display never uses an exponent for floats
+        let repr = f.abs().to_string();
+        let (credits, fraction) = repr.split_once('.').unwrap_or((&repr, ""));
+        let digit = |i: usize| {
+            fraction
+                .as_bytes()
+                .get(i)
+                .map_or(0, |d| u64::from(d - b'0'))
+        };
+        let cents = digit(0) * 10 + digit(1) + u64::from(digit(2) >= 5);
+
+        credits
+            .parse::<u64>()
+            .ok()
+            .and_then(|v| v.checked_mul(100))
+            .and_then(|v| v.checked_add(cents))
+            .map(Currency)
+            .ok_or(PoksError::CurrencyOverflow)
+    }
+
+    /// Add two amounts, [`None`] if the sum would overflow
+    pub const fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.0.checked_add(rhs.0) {
+            Some(v) => Some(Self(v)),
+            None => None,
+        }
+    }
+
+    /// Subtract `rhs`, [`None`] if it is more than this amount
+    pub const fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.0.checked_sub(rhs.0) {
+            Some(v) => Some(Self(v)),
+            None => None,
+        }
+    }
+
+    /// Multiply this amount by `rhs`, [`None`] if the product would overflow
+    pub const fn checked_mul(self, rhs: u64) -> Option<Self> {
+        match self.0.checked_mul(rhs) {
+            Some(v) => Some(Self(v)),
+            None => None,
+        }
+    }
+
+    /// Add two amounts, the largest amount if the sum would overflow
+    pub const fn saturating_add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+
+    /// Subtract `rhs`, [`Currency::ZERO`] if it is more than this amount
+    pub const fn saturating_sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+
+    /// Multiply this amount by `rhs`, the largest amount if the product would overflow
+    pub const fn saturating_mul(self, rhs: u64) -> Self {
+        Self(self.0.saturating_mul(rhs))
+    }
+
+    /// Add two amounts, failing with [`PoksError::CurrencyOverflow`] instead of wrapping
+    pub fn try_add(self, rhs: Self) -> Result<Self, PoksError> {
+        self.checked_add(rhs).ok_or(PoksError::CurrencyOverflow)
+    }
+
+    /// Divide this amount between `n` parties, returning the share of each and the cents that
+    /// are left over
+    ///
+    /// The remainder is less than one cent per party, who gets it is up to the caller. Splitting
+    /// between nobody leaves the whole amount as remainder.
+    #[must_use]
+    pub const fn split_evenly(self, n: usize) -> (Self, Self) {
+        if n == 0 {
+            return (Self::ZERO, self);
+        }
+        let n = n as u64;
+        (Self(self.0 / n), Self(self.0 % n))
+    }
+
+    /// Express this amount as a multiple of the given big blind
+    ///
+    /// Returns `0.0` if the big blind is zero.
+    pub fn in_blinds(&self, big_blind: Currency) -> f64 {
+        if big_blind == Self::ZERO {
+            return 0.0;
+        }
+        self.0 as f64 / big_blind.0 as f64
+    }
 }
 
 impl Deref for Currency {
@@ -86,35 +275,84 @@ impl From<u64> for Currency {
 
 impl Display for Currency {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let creds = self.credits();
-        let cents = self.cents();
+        write!(f, "{}", self.format(FormatOpts::default()))
+    }
+}
 
-        // Format main units with thousands separators
-        let main_str = if creds == 0 {
-            "0".to_string()
-        } else {
-            let mut result = String::new();
-            let main_str = creds.to_string();
-
-            for (i, ch) in main_str.chars().rev().enumerate() {
-                if i > 0 && i % 3 == 0 {
-                    result.push(Self::THOUSANDS_SEPARATOR);
-                }
-                result.push(ch);
-            }
+impl FromStr for Currency {
+    type Err = PoksError;
+
+    /// Parse an amount in the format produced by [`Display`], like `1.234,56ŧ`
+    ///
+    /// The thousands separators and the currency symbol are optional, the cents may be left out
+    /// or have one or two digits. A leading `-` is accepted, but since amounts can not be
+    /// negative, only for zero.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = |reason: &str| PoksError::currency_parse(s, reason);
+        let amount = s.trim();
+        if let Some(amount) = amount.strip_prefix('-') {
+            return match amount.parse::<Currency>() {
+                Ok(Self::ZERO) if !amount.starts_with('-') => Ok(Self::ZERO),
+                Ok(_) => Err(err("negative amounts are not supported")),
+                Err(_) => Err(err("not a valid amount after the sign")),
+            };
+        }
+        let amount = amount
+            .strip_suffix(Self::CURRENCY_SYMBOL)
+            .unwrap_or(amount)
+            .trim_end();
+
+        let (credits, cents) = match amount.split_once(Self::DECIMAL_SEPARATOR) {
+            Some((credits, cents)) => (credits, Some(cents)),
+            None => (amount, None),
+        };
 
-            result.chars().rev().collect()
+        let groups: Vec<&str> = credits.split(Self::THOUSANDS_SEPARATOR).collect();
+        if groups.len() > 1
+            && (groups[0].is_empty()
+                || groups[0].len() > 3
+                || groups[1..].iter().any(|g| g.len() != 3))
+        {
+            return Err(err("misplaced thousands separator"));
+        }
+        let credits = groups.concat();
+        if credits.is_empty() || !credits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(err("credits are not a number"));
+        }
+        let credits: u64 = credits.parse().map_err(|_| PoksError::CurrencyOverflow)?;
+
+        let cents: u64 = match cents {
+            None => 0,
+            Some(cents)
+                if (1..=2).contains(&cents.len()) && cents.chars().all(|c| c.is_ascii_digit()) =>
+            {
+                let value: u64 = cents.parse().map_err(|_| err("cents are not a number"))?;
+                if cents.len() == 1 { value * 10 } else { value }
+            }
+            Some(_) => return Err(err("cents need to have one or two digits")),
         };
 
-        // Combine everything
-        write!(
-            f,
-            "{}{}{:02}{}",
-            main_str,
-            Self::DECIMAL_SEPARATOR,
-            cents,
-            Self::CURRENCY_SYMBOL
-        )
+        credits
+            .checked_mul(100)
+            .and_then(|v| v.checked_add(cents))
+            .map(Currency)
+            .ok_or(PoksError::CurrencyOverflow)
+    }
+}
+
+impl TryFrom<&str> for Currency {
+    type Error = PoksError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = PoksError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
     }
 }
 
@@ -134,6 +372,54 @@ impl Sub for Currency {
     }
 }
 
+impl Add<&Currency> for Currency {
+    type Output = Currency;
+
+    fn add(self, rhs: &Currency) -> Self::Output {
+        Currency(self.0 + rhs.0)
+    }
+}
+
+impl Add<Currency> for &Currency {
+    type Output = Currency;
+
+    fn add(self, rhs: Currency) -> Self::Output {
+        Currency(self.0 + rhs.0)
+    }
+}
+
+impl Add<&Currency> for &Currency {
+    type Output = Currency;
+
+    fn add(self, rhs: &Currency) -> Self::Output {
+        Currency(self.0 + rhs.0)
+    }
+}
+
+impl Sub<&Currency> for Currency {
+    type Output = Currency;
+
+    fn sub(self, rhs: &Currency) -> Self::Output {
+        Currency(self.0 - rhs.0)
+    }
+}
+
+impl Sub<Currency> for &Currency {
+    type Output = Currency;
+
+    fn sub(self, rhs: Currency) -> Self::Output {
+        Currency(self.0 - rhs.0)
+    }
+}
+
+impl Sub<&Currency> for &Currency {
+    type Output = Currency;
+
+    fn sub(self, rhs: &Currency) -> Self::Output {
+        Currency(self.0 - rhs.0)
+    }
+}
+
 impl Mul for Currency {
     type Output = Self;
 
@@ -178,6 +464,18 @@ impl SubAssign for Currency {
     }
 }
 
+impl AddAssign<&Currency> for Currency {
+    fn add_assign(&mut self, rhs: &Currency) {
+        self.0 += rhs.0
+    }
+}
+
+impl SubAssign<&Currency> for Currency {
+    fn sub_assign(&mut self, rhs: &Currency) {
+        self.0 -= rhs.0
+    }
+}
+
 impl MulAssign for Currency {
     fn mul_assign(&mut self, rhs: Self) {
         self.0 *= rhs.0
@@ -212,6 +510,16 @@ impl Sum for Currency {
     }
 }
 
+impl<'a> Sum<&'a Currency> for Currency {
+    fn sum<I: Iterator<Item = &'a Currency>>(iter: I) -> Self {
+        let mut acc = Currency::new(0, 0);
+        for c in iter {
+            acc += c;
+        }
+        acc
+    }
+}
+
 impl Product for Currency {
     fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
         let mut acc = Currency::new(0, 0);
@@ -224,7 +532,8 @@ impl Product for Currency {
 
 #[cfg(test)]
 mod test {
-    use crate::currency::Currency;
+    use crate::currency::{Currency, FormatOpts};
+    use crate::errors::PoksError;
 
     #[test]
     fn test_currency_display() {
@@ -246,6 +555,46 @@ mod test {
         assert_eq!(CU!(0, 50).to_string(), "0,50ŧ");
     }
 
+    #[test]
+    fn test_currency_format() {
+        let amount = CU!(1234, 56);
+        assert_eq!(amount.format(FormatOpts::default()), amount.to_string());
+        assert_eq!(amount.format(FormatOpts::US), "1,234.56");
+        assert_eq!(amount.format(FormatOpts::PLAIN), "1234.56");
+        assert_eq!(CU!(1234567).format(FormatOpts::US), "1,234,567.00");
+        assert_eq!(CU!(0, 5).format(FormatOpts::US), "0.05");
+
+        let no_symbol = FormatOpts {
+            symbol: false,
+            ..Default::default()
+        };
+        assert_eq!(amount.format(no_symbol), "1.234,56");
+        let credits = FormatOpts {
+            cents: false,
+            ..Default::default()
+        };
+        assert_eq!(amount.format(credits), "1.235ŧ");
+        assert_eq!(CU!(999, 49).format(credits), "999ŧ");
+    }
+
+    #[test]
+    fn test_currency_parse() {
+        for value in (0..12)
+            .map(|exp| 10u64.pow(exp))
+            .chain([0, 150, 50, 123456])
+        {
+            let cu = Currency(value);
+            assert_eq!(cu.to_string().parse::<Currency>().unwrap(), cu);
+        }
+        assert_eq!("1.234,56ŧ".parse::<Currency>().unwrap(), CU!(1234, 56));
+        assert_eq!("1234,5".parse::<Currency>().unwrap(), CU!(1234, 50));
+        assert_eq!("-0,00ŧ".parse::<Currency>().unwrap(), Currency::ZERO);
+        assert!("-1,00ŧ".parse::<Currency>().is_err());
+        assert!("--0".parse::<Currency>().is_err());
+        assert!("1,2,3".parse::<Currency>().is_err());
+        assert!("abc".parse::<Currency>().is_err());
+    }
+
     #[test]
     fn test_currency_calc() {
         assert_eq!(Currency(1) + Currency(99), Currency(100));
@@ -256,10 +605,96 @@ mod test {
         assert_eq!(Currency(33) % Currency(10), Currency(3));
     }
 
+    #[test]
+    fn test_currency_calc_refs() {
+        let amounts = [CU!(1), CU!(2, 50), CU!(0, 50)];
+        assert_eq!(amounts.iter().sum::<Currency>(), CU!(4));
+        assert_eq!(&amounts[0] + &amounts[1], CU!(3, 50));
+        assert_eq!(&amounts[1] - &amounts[2], CU!(2));
+        assert_eq!(amounts[0] + &amounts[2], CU!(1, 50));
+        let mut acc = Currency::ZERO;
+        for amount in &amounts {
+            acc += amount;
+        }
+        acc -= &amounts[0];
+        assert_eq!(acc, CU!(3));
+    }
+
     #[test]
     fn test_currency_roundct() {
         assert_eq!(CU!(1, 33).round_cents(), CU!(1));
         assert_eq!(CU!(1, 49).round_cents(), CU!(1));
         assert_eq!(CU!(1, 50).round_cents(), CU!(2));
     }
+
+    #[test]
+    fn test_currency_try_from() {
+        assert_eq!(Currency::try_from("1,50ŧ").unwrap(), CU!(1, 50));
+        assert_eq!(
+            Currency::try_from("1.000,5".to_string()).unwrap(),
+            CU!(1000, 50)
+        );
+        assert_eq!(Currency::try_from("42").unwrap(), CU!(42));
+        assert!(Currency::try_from("1,505ŧ").is_err());
+        assert!(Currency::try_from("10.00,00ŧ").is_err());
+        assert!(Currency::try_from("ŧ").is_err());
+    }
+
+    #[test]
+    fn test_currency_checked() {
+        let max = Currency(u64::MAX);
+        assert_eq!(max.checked_add(Currency::ONE_CT), None);
+        assert_eq!(CU!(1).checked_add(CU!(0, 50)), Some(CU!(1, 50)));
+        assert_eq!(Currency::ZERO.checked_sub(Currency::ONE_CT), None);
+        assert_eq!(max.checked_mul(2), None);
+        assert_eq!(max.saturating_add(CU!(1)), max);
+        assert_eq!(CU!(1).saturating_sub(CU!(2)), Currency::ZERO);
+        assert!(matches!(
+            max.try_add(CU!(1)),
+            Err(PoksError::CurrencyOverflow)
+        ));
+    }
+
+    #[test]
+    fn test_currency_split_evenly() {
+        assert_eq!(CU!(100).split_evenly(3), (CU!(33, 33), CU!(0, 1)));
+        assert_eq!(CU!(100).split_evenly(4), (CU!(25), CU!(0)));
+        assert_eq!(CU!(0, 2).split_evenly(3), (CU!(0), CU!(0, 2)));
+        assert_eq!(CU!(100).split_evenly(0), (CU!(0), CU!(100)));
+    }
+
+    #[test]
+    fn test_currency_from_float() {
+        // the closest f64 to 1.005 is 1.00499999999999989..., rounding is half-up on 1.005
+        assert_eq!(Currency::from_float(1.005), CU!(1, 1));
+        assert_eq!(Currency::from_float(2.675), CU!(2, 68));
+        assert_eq!(Currency::from_float(0.125), CU!(0, 13));
+        assert_eq!(Currency::from_float(1.004), CU!(1));
+        assert_eq!(Currency::from_float(1.995), CU!(2));
+        assert_eq!(Currency::from_float(-0.0), Currency::ZERO);
+        assert_eq!(
+            Currency::from_float(CU!(1234, 56).as_float()),
+            CU!(1234, 56)
+        );
+
+        assert!(matches!(
+            Currency::try_from_float(f64::NAN),
+            Err(PoksError::CurrencyParseError { .. })
+        ));
+        assert!(Currency::try_from_float(f64::INFINITY).is_err());
+        assert!(Currency::try_from_float(-1.0).is_err());
+        assert!(matches!(
+            Currency::try_from_float(1e30),
+            Err(PoksError::CurrencyOverflow)
+        ));
+        assert_eq!(Currency::from_float(f64::NAN), Currency::ZERO);
+        assert_eq!(Currency::from_float(1e30), Currency(u64::MAX));
+    }
+
+    #[test]
+    fn test_currency_in_blinds() {
+        assert_eq!(CU!(5000).in_blinds(CU!(100)), 50.0);
+        assert_eq!(CU!(1, 50).in_blinds(CU!(1)), 1.5);
+        assert_eq!(CU!(10).in_blinds(Currency::ZERO), 0.0);
+    }
 }
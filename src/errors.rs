@@ -1,6 +1,7 @@
 use crate::currency::Currency;
 use crate::game::PlayerID;
 use crate::players::PlayerState;
+use poker::Card;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, PoksError>;
@@ -26,19 +27,43 @@ pub enum PoksError {
         current_state: PlayerState,
     },
 
+    #[error("It is not the turn of player {player_id}, but of player {turn}")]
+    NotPlayersTurn { player_id: PlayerID, turn: PlayerID },
+
     #[error("Player {player_id} is already all-in")]
     PlayerAlreadyAllIn { player_id: PlayerID },
 
+    #[error("Player {player_id} has no chips left to act with")]
+    CannotActWithZeroStack { player_id: PlayerID },
+
+    #[error("No player is able to act")]
+    NoActivePlayers,
+
     #[error("Not enough players to start game (need at least 2, have {count})")]
     InsufficientPlayers { count: usize },
 
     #[error("Too many players for deck (requested: {requested}, max supported: {max})")]
     TooManyPlayers { requested: usize, max: usize },
 
+    #[error("Session is over, player {winner} has all the chips")]
+    SessionOver { winner: PlayerID },
+
+    #[error("Unknown phase: {input:?}")]
+    InvalidPhase { input: String },
+
+    #[error("Invalid seed {input:?}: expected 64 hex digits")]
+    InvalidSeed { input: String },
+
+    #[error("The replay does not match the recorded hand: {reason}")]
+    ReplayMismatch { reason: String },
+
     // Action/Betting Errors
     #[error("Invalid action: cannot call when you're not under the round bet")]
     InvalidCall,
 
+    #[error("Invalid action: cannot check while facing a bet of {to_call}")]
+    InvalidCheck { to_call: Currency },
+
     // Action/Betting Errors
     #[error("Invalid call amount: expected {expected}, got {actual}")]
     CallAmountMismatch {
@@ -46,6 +71,12 @@ pub enum PoksError {
         actual: Currency,
     },
 
+    #[error("Invalid all in amount: the stack is {stack}, got {actual}")]
+    AllInAmountMismatch { stack: Currency, actual: Currency },
+
+    #[error("Invalid command {input:?}: {reason}")]
+    InvalidCommand { input: String, reason: String },
+
     #[error("Cannot raise: betting is not allowed in current game state")]
     RaiseNotAllowed,
 
@@ -58,10 +89,25 @@ pub enum PoksError {
     #[error("Invalid bet amount: {amount} (minimum: {minimum})")]
     TooLowBetAmount { amount: Currency, minimum: Currency },
 
+    #[error("Invalid bet amount: {amount} (maximum: {maximum})")]
+    TooHighBetAmount { amount: Currency, maximum: Currency },
+
+    #[error("Betting is not closed yet")]
+    BettingNotClosed,
+
     // Card/Deck Errors
     #[error("Not enough cards in deck")]
     InsufficientCards,
 
+    #[error("Expected {expected} cards, got {actual}")]
+    CardCountMismatch { expected: usize, actual: usize },
+
+    #[error("The board already holds all {max} community cards")]
+    BoardFull { max: usize },
+
+    #[error("Card {card} is contained more than once")]
+    DuplicateCard { card: Card },
+
     #[error("Card evaluation failed: {reason}")]
     CardEvaluationError { reason: String },
 
@@ -72,6 +118,9 @@ pub enum PoksError {
     #[error("Currency overflow in transaction")]
     CurrencyOverflow,
 
+    #[error("Could not parse currency from {input:?}: {reason}")]
+    CurrencyParseError { input: String, reason: String },
+
     // World/Player Management Errors
     #[error("Player action timeout")]
     PlayerTimeout,
@@ -153,6 +202,13 @@ impl PoksError {
     pub fn too_many_players(requested: usize, max: usize) -> Self {
         Self::TooManyPlayers { requested, max }
     }
+
+    pub fn currency_parse(input: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::CurrencyParseError {
+            input: input.into(),
+            reason: reason.into(),
+        }
+    }
 }
 
 // Helper trait for adding context to results
@@ -0,0 +1,458 @@
+//! Turn order and betting rounds
+
+use crate::Result;
+use crate::errors::PoksError;
+use crate::game::{Game, GameState, Phase, Player, PlayerID};
+use crate::players::PlayerState;
+
+impl Game {
+    /// Move the turn to the next player who can still act
+    ///
+    /// Players who folded, lost, paused or are all in are skipped. The search wraps around the
+    /// table and ends at the current player again if nobody else can act. Raising is disallowed
+    /// for the new player if an all in below a full raise did not reopen the betting for them,
+    /// or if the street reached the cap of a fixed limit game.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::NoActivePlayers`] if no player can act anymore.
+    pub(crate) fn advance_turn(&mut self) -> Result<()> {
+        let n = self.players.len();
+        for offset in 1..=n {
+            let pid = (self.turn + offset) % n;
+            if self.players[pid].state == PlayerState::Playing {
+                self.turn = pid;
+                self.state = if self.players[pid].can_raise && !self.raise_cap_reached() {
+                    GameState::RaiseAllowed
+                } else {
+                    GameState::RaiseDisallowed
+                };
+                return Ok(());
+            }
+        }
+        Err(PoksError::NoActivePlayers)
+    }
+
+    /// Whether the betting round of the current street is over
+    ///
+    /// Every player who can still act needs to have acted since the last raise and to match the
+    /// highest bet of the round. Posting a blind is not acting, so the big blind gets the option
+    /// to raise if everyone just calls. If at most one player can still act and they match the
+    /// highest bet, nobody is left to bet against.
+    #[must_use]
+    pub fn is_betting_complete(&self) -> bool {
+        let highest = self.highest_bet_of_round();
+        let can_act: Vec<&Player> = self
+            .players
+            .iter()
+            .filter(|p| p.state == PlayerState::Playing)
+            .collect();
+        if can_act.len() <= 1 {
+            return can_act.iter().all(|p| p.round_bet >= highest);
+        }
+        can_act.iter().all(|p| p.acted && p.round_bet == highest)
+    }
+
+    /// Pass the turn on, dealing the next streets once the betting round is complete
+    pub(crate) fn next_turn(&mut self) -> Result<()> {
+        if self.players.iter().filter(|p| p.state.is_playing()).count() < 2 {
+            return Ok(()); // the hand is decided
+        }
+        if !self.is_betting_complete() {
+            return self.advance_turn();
+        }
+        while self.is_betting_complete() {
+            if self.run_it > 1 && self.is_all_in_runout() {
+                self.run_it_twice(self.run_it)?;
+                return Ok(());
+            }
+            self.advance_phase()?;
+            if self.is_finished() {
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+
+    /// The player who opens the betting round of the current street
+    ///
+    /// This is the first player left of the dealer who can still act. Preflop, the player left
+    /// of the big blind opens instead, which is the dealer in heads-up. If nobody can act, this
+    /// is the dealer, or the big blind preflop.
+    #[must_use]
+    pub fn first_to_act_this_street(&self) -> PlayerID {
+        let start = match self.phase {
+            Phase::Preflop => self.big_blind_position(),
+            _ => self.dealer,
+        };
+        let n = self.players.len();
+        (1..=n)
+            .map(|offset| (start + offset) % n)
+            .find(|pid| self.players[*pid].state == PlayerState::Playing)
+            .unwrap_or(start)
+    }
+
+    /// Prepare the betting round of the current street
+    ///
+    /// Forgets who acted and who raised last, allows raising by at least the big blind again, or
+    /// the bet of the street in fixed limit games, and gives the turn to the
+    /// [first player to act](Game::first_to_act_this_street). Bets are left alone, as
+    /// [`set_phase`](Game::set_phase) already moved the round bets into the total bets.
+    pub(crate) fn start_betting(&mut self) {
+        for player in self.players.iter_mut() {
+            player.acted = false;
+            player.can_raise = true;
+        }
+        self.last_aggressor = None;
+        self.min_raise = self.limit.opening_bet(self.phase, self.big_blind);
+        // the big blind is the opening bet before the flop
+        self.bets_this_street = u8::from(self.phase == Phase::Preflop);
+        self.state = GameState::RaiseAllowed;
+        self.turn = self.first_to_act_this_street();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CU;
+    use crate::currency::Currency;
+    use crate::errors::PoksError;
+    use crate::game::{
+        Action, ActionOutcome, Game, GameState, Phase, PlayerID, Winner, test_seats,
+    };
+    use crate::players::PlayerState;
+
+    #[test]
+    fn test_raise_closes_after_callers() {
+        let mut game = Game::build(&test_seats(4, CU!(5000)), 0).unwrap();
+        let raiser = game.turn();
+        game.process_action(Some(Action::Raise(CU!(10)))).unwrap();
+        assert_eq!(game.last_aggressor(), Some(raiser));
+
+        for caller in 1..=3 {
+            assert_eq!(game.phase(), Phase::Preflop);
+            assert_eq!(game.turn(), (raiser + caller) % 4);
+            assert!(!game.is_betting_complete());
+            let call = game.action_call();
+            game.process_action(Some(call)).unwrap();
+        }
+        assert_eq!(game.phase(), Phase::Flop);
+        assert_eq!(game.last_aggressor(), None);
+    }
+
+    #[test]
+    fn test_start_betting() {
+        let mut game = Game::build(&test_seats(4, CU!(5000)), 0).unwrap();
+        assert_eq!(game.turn(), 3); // left of the big blind
+
+        game.advance_phase().unwrap();
+        assert_eq!(game.phase(), Phase::Flop);
+        assert_eq!(game.turn(), game.small_blind_position());
+        assert_eq!(game.state(), GameState::RaiseAllowed);
+        assert_eq!(game.highest_bet_of_round(), CU!(0));
+        assert!(game.players().iter().all(|p| p.round_bet() == CU!(0)));
+        assert_eq!(game.players()[2].total_bet(), CU!(1));
+
+        let heads_up = Game::build(&test_seats(2, CU!(5000)), 1).unwrap();
+        assert_eq!(heads_up.turn(), 1); // the dealer opens heads-up
+    }
+
+    #[test]
+    fn test_first_to_act_this_street() {
+        let mut game = Game::build(&test_seats(6, CU!(5000)), 0).unwrap();
+        assert_eq!(game.first_to_act_this_street(), 3); // under the gun
+        assert_eq!(game.turn(), 3);
+
+        game.advance_phase().unwrap();
+        assert_eq!(game.first_to_act_this_street(), 1); // the small blind
+        assert_eq!(game.turn(), 1);
+
+        // folded players are skipped
+        game.players[1].state = PlayerState::Folded;
+        assert_eq!(game.first_to_act_this_street(), 2);
+
+        let mut heads_up = Game::build(&test_seats(2, CU!(5000)), 0).unwrap();
+        assert_eq!(heads_up.first_to_act_this_street(), 0);
+        heads_up.advance_phase().unwrap();
+        assert_eq!(heads_up.first_to_act_this_street(), 1);
+    }
+
+    /// Build a game where seat 1 has `short_stack` and the others 5000
+    ///
+    /// With the dealer at 0, the small blind is seat 1, the big blind seat 2 and seat 3 opens.
+    fn game_with_short_stack(short_stack: Currency) -> Game {
+        let seats = test_seats(4, CU!(5000));
+        seats[1].set_currency(short_stack);
+        Game::build(&seats, 0).unwrap()
+    }
+
+    fn all_in(game: &mut Game) {
+        let stack = game.players()[game.turn()].currency();
+        game.process_action(Some(Action::AllIn(stack))).unwrap();
+    }
+
+    #[test]
+    fn test_can_afford_raise_exceeding_stack() {
+        let mut game = game_with_short_stack(CU!(50));
+        // the small blind is already posted from the short stack
+        assert!(game.player_can_afford(1, Action::Raise(CU!(49, 50))));
+        assert!(!game.player_can_afford(1, Action::Raise(CU!(49, 51))));
+        assert!(game.player_can_afford(0, Action::Raise(CU!(51))));
+        assert!(!game.player_can_afford(4, Action::Fold));
+
+        game.process_action(Some(Action::Raise(CU!(30)))).unwrap(); // seat 3 bets 30
+        // a raise first has to cover the call portion
+        assert!(!game.player_can_afford(0, Action::Raise(CU!(30))));
+        assert!(game.player_can_afford(0, Action::Raise(CU!(60))));
+        assert!(!game.player_can_afford(1, Action::Raise(CU!(20))));
+    }
+
+    #[test]
+    fn test_can_afford_call_with_insufficient_funds() {
+        let mut game = game_with_short_stack(CU!(50));
+        game.process_action(Some(Action::Raise(CU!(100)))).unwrap(); // seat 3 bets 100
+        let call = game.action_call();
+        assert!(game.player_can_afford(0, call));
+        assert!(!game.player_can_afford(1, call));
+        // the short stack can still go all in or fold
+        assert!(game.player_can_afford(1, Action::AllIn(CU!(50))));
+        assert!(game.player_can_afford(1, Action::Fold));
+    }
+
+    fn can_raise(game: &Game, pid: PlayerID) -> bool {
+        game.legal_actions(pid)
+            .iter()
+            .any(|a| matches!(a, Action::Raise(_)))
+    }
+
+    #[test]
+    fn test_flop_after_seat_zero_folds() {
+        // seat 0 is the small blind and folds, seat 1 is the big blind
+        let mut game = Game::build(&test_seats(4, CU!(5000)), 3).unwrap();
+        assert_eq!(game.turn(), 2);
+        for _ in 0..2 {
+            let call = game.action_call();
+            game.process_action(Some(call)).unwrap();
+        }
+        assert_eq!(game.turn(), 0);
+        game.process_action(Some(Action::Fold)).unwrap();
+        assert_eq!(game.phase(), Phase::Preflop);
+        assert_eq!(game.turn(), 1);
+        game.process_action(Some(Action::Check)).unwrap();
+
+        assert_eq!(game.phase(), Phase::Flop);
+        assert_eq!(game.community_cards().len(), 3);
+        // the first seat left of the dealer that is still in the hand opens
+        assert_eq!(game.turn(), 1);
+        for pid in [1, 2, 3] {
+            assert_eq!(game.phase(), Phase::Flop);
+            assert_eq!(game.turn(), pid);
+            game.process_action(Some(Action::Check)).unwrap();
+        }
+        assert_eq!(game.phase(), Phase::Turn);
+        assert_eq!(game.turn(), 1);
+    }
+
+    #[test]
+    fn test_all_in_below_min_raise() {
+        let mut game = game_with_short_stack(CU!(380));
+        game.process_action(Some(Action::Raise(CU!(100)))).unwrap(); // seat 3 bets 100
+        game.process_action(Some(Action::Raise(CU!(300)))).unwrap(); // seat 0 raises to 300
+        assert_eq!(game.min_raise_amount(), CU!(200));
+        all_in(&mut game); // seat 1 goes all in for 380, only raising by 80
+        assert_eq!(game.highest_bet_of_round(), CU!(380));
+        assert_eq!(game.min_raise_amount(), CU!(200));
+
+        // seat 3 still faces the full raise of seat 0 and may raise again
+        assert!(can_raise(&game, 3));
+        // seat 0 only faces the incomplete raise since acting, the betting is not reopened
+        assert!(!can_raise(&game, 0));
+
+        assert_eq!(game.turn(), 2);
+        game.process_action(Some(Action::Fold)).unwrap();
+        let call = game.action_call();
+        game.process_action(Some(call)).unwrap();
+        assert_eq!(game.turn(), 0);
+        assert_eq!(game.state(), GameState::RaiseDisallowed);
+        assert!(matches!(
+            game.process_action(Some(Action::Raise(CU!(500)))),
+            Err(PoksError::RaiseNotAllowed)
+        ));
+        let call = game.action_call();
+        game.process_action(Some(call)).unwrap();
+        assert_eq!(game.phase(), Phase::Flop);
+        assert_eq!(game.state(), GameState::RaiseAllowed);
+    }
+
+    #[test]
+    fn test_all_in_below_min_raise_after_calls() {
+        let mut game = game_with_short_stack(CU!(150));
+        game.process_action(Some(Action::Raise(CU!(100)))).unwrap(); // seat 3 bets 100
+        let call = game.action_call();
+        game.process_action(Some(call)).unwrap(); // seat 0 calls
+        all_in(&mut game); // seat 1 goes all in for 150
+
+        // the big blind did not act yet and may raise, the others only call or fold
+        assert!(can_raise(&game, 2));
+        assert!(!can_raise(&game, 3));
+        assert!(!can_raise(&game, 0));
+        assert!(game.legal_actions(3).contains(&Action::Call(CU!(50))));
+    }
+
+    #[test]
+    fn test_valid_actions_short_stack() {
+        let mut game = game_with_short_stack(CU!(8));
+        game.process_action(Some(Action::Raise(CU!(5)))).unwrap(); // seat 3 raises to 5
+        let call = game.action_call();
+        game.process_action(Some(call)).unwrap(); // seat 0 calls
+
+        // seat 1 has 7,50 left after the small blind, a raise to at least 9 is out of reach
+        assert_eq!(game.turn(), 1);
+        assert_eq!(
+            game.valid_actions(),
+            [
+                Action::Fold,
+                Action::Call(CU!(4, 50)),
+                Action::AllIn(CU!(7, 50))
+            ]
+        );
+        assert!(game.valid_actions().contains(&game.action_call()));
+    }
+
+    #[test]
+    fn test_valid_actions_all_in_only() {
+        let mut game = game_with_short_stack(CU!(3));
+        game.process_action(Some(Action::Raise(CU!(5)))).unwrap();
+        let call = game.action_call();
+        game.process_action(Some(call)).unwrap();
+
+        // seat 1 can not even call, only fold or put in the rest
+        assert_eq!(game.turn(), 1);
+        assert_eq!(
+            game.valid_actions(),
+            [Action::Fold, Action::AllIn(CU!(2, 50))]
+        );
+    }
+
+    #[test]
+    fn test_raise_below_min_raise() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        game.process_action(Some(Action::Raise(CU!(10)))).unwrap(); // raises to 10 by 9
+        assert!(matches!(
+            game.process_action(Some(Action::Raise(CU!(15)))),
+            Err(PoksError::TooLowBetAmount { .. })
+        ));
+    }
+
+    #[test]
+    fn test_min_raise_opening() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        assert_eq!(game.min_raise_amount(), game.big_blind());
+        game.process_action(Some(Action::Raise(CU!(10)))).unwrap(); // raises to 10 by 9
+        assert_eq!(game.min_raise_amount(), CU!(9));
+
+        game.advance_phase().unwrap();
+        // nobody bet on the flop yet, the big blind is the smallest bet
+        assert_eq!(game.min_raise_amount(), game.big_blind());
+        assert!(matches!(
+            game.process_action(Some(Action::Raise(CU!(0, 50)))),
+            Err(PoksError::TooLowBetAmount { .. })
+        ));
+        game.process_action(Some(Action::Raise(CU!(10)))).unwrap();
+        assert_eq!(game.min_raise_amount(), CU!(10));
+    }
+
+    #[test]
+    fn test_min_raise_reraise() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        game.advance_phase().unwrap();
+        game.process_action(Some(Action::Raise(CU!(10)))).unwrap(); // bets 10
+        game.process_action(Some(Action::Raise(CU!(20)))).unwrap(); // raises to 20
+        assert_eq!(game.min_raise_amount(), CU!(10));
+        assert!(
+            game.legal_actions(game.turn())
+                .contains(&Action::Raise(CU!(30)))
+        );
+
+        // the next raise has to go to at least 30
+        assert!(matches!(
+            game.process_action(Some(Action::Raise(CU!(29)))),
+            Err(PoksError::TooLowBetAmount { .. })
+        ));
+        game.process_action(Some(Action::Raise(CU!(50)))).unwrap(); // raises to 50 by 30
+        assert_eq!(game.min_raise_amount(), CU!(30));
+    }
+
+    #[test]
+    fn test_big_blind_option() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        while game.turn() != game.big_blind_position() {
+            let call = game.action_call();
+            game.process_action(Some(call)).unwrap();
+        }
+        // everyone called, but the big blind did not act yet
+        assert_eq!(game.phase(), Phase::Preflop);
+        assert!(!game.is_betting_complete());
+        assert_eq!(game.action_call(), Action::check());
+
+        game.process_action(Some(Action::check())).unwrap();
+        assert_eq!(game.phase(), Phase::Flop);
+    }
+
+    #[test]
+    fn test_check_facing_a_bet() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        // the first player faces the big blind
+        assert!(matches!(
+            game.process_action(Some(Action::Check)),
+            Err(PoksError::InvalidCheck { to_call }) if to_call == CU!(1)
+        ));
+        assert!(!game.valid_actions().contains(&Action::Check));
+        assert_eq!(game.action_call(), Action::Call(CU!(1)));
+
+        while game.phase() == Phase::Preflop {
+            let call = game.action_call();
+            game.process_action(Some(call)).unwrap();
+        }
+        // nobody bet on the flop yet, there is nothing to call
+        assert!(game.valid_actions().contains(&Action::Check));
+        assert!(matches!(
+            game.process_action(Some(Action::Call(CU!(0)))),
+            Err(PoksError::InvalidCall)
+        ));
+        game.process_action(Some(Action::Check)).unwrap();
+        assert_eq!(game.last_action().unwrap().1, Action::Check);
+    }
+
+    #[test]
+    fn test_big_blind_walk() {
+        let seats = test_seats(3, CU!(5000));
+        let mut game = Game::build(&seats, 0).unwrap();
+        let (sb, bb) = (game.small_blind_position(), game.big_blind_position());
+        assert_eq!((game.turn(), sb, bb), (0, 1, 2));
+
+        assert_eq!(
+            game.process_action(Some(Action::Fold)).unwrap(),
+            ActionOutcome::Applied
+        );
+        assert_eq!(
+            game.process_action(Some(Action::Fold)).unwrap(),
+            ActionOutcome::Finished
+        );
+
+        // the big blind takes its own blind back and wins the dead small blind
+        assert_eq!(game.phase(), Phase::Preflop);
+        assert!(game.community_cards().is_empty());
+        assert_eq!(game.winner(), Some(Winner::UnknownCards(CU!(1, 50), bb)));
+        assert_eq!(game.winners().len(), 1);
+        assert_eq!(seats[bb].currency(), CU!(5000, 50));
+        assert_eq!(seats[sb].currency(), CU!(4999, 50));
+        assert_eq!(seats[0].currency(), CU!(5000));
+        // the big blind does not get to act anymore
+        assert_eq!(
+            game.process_action(Some(Action::Check)).unwrap(),
+            ActionOutcome::Finished
+        );
+        assert_eq!(seats[bb].currency(), CU!(5000, 50));
+    }
+}
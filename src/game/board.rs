@@ -0,0 +1,142 @@
+//! The community cards on the table
+
+use std::ops::Deref;
+
+use poker::Card;
+
+use crate::Result;
+use crate::errors::PoksError;
+use crate::game::{BoardByStreet, Cards, CardsDynamic, Phase};
+
+/// The community cards, in the order they were dealt
+///
+/// Derefs to the dealt cards. Cards can only be added, and no more than
+/// [`Board::MAX_CARDS`] of them.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Board {
+    cards: CardsDynamic,
+}
+
+impl Board {
+    /// A full board: the flop, the turn and the river
+    pub const MAX_CARDS: usize = 5;
+
+    pub const fn new() -> Self {
+        Self {
+            cards: CardsDynamic::new(),
+        }
+    }
+
+    /// Put the next card on the table
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::BoardFull`] if the river was already dealt.
+    pub fn push(&mut self, card: Card) -> Result<()> {
+        if self.cards.len() >= Self::MAX_CARDS {
+            return Err(PoksError::BoardFull {
+                max: Self::MAX_CARDS,
+            });
+        }
+        self.cards.push(card);
+        Ok(())
+    }
+
+    /// The street this board was dealt up to
+    ///
+    /// A flop that is not completely dealt yet still counts as [`Phase::Preflop`].
+    #[must_use]
+    pub fn phase(&self) -> Phase {
+        let mut phase = Phase::Preflop;
+        while let Some(next) = phase.next().filter(|p| p.board_len() <= self.cards.len()) {
+            phase = next;
+        }
+        phase
+    }
+
+    #[must_use]
+    pub fn flop(&self) -> Option<Cards<3>> {
+        self.by_street().flop
+    }
+
+    #[must_use]
+    pub fn turn(&self) -> Option<Card> {
+        self.by_street().turn
+    }
+
+    #[must_use]
+    pub fn river(&self) -> Option<Card> {
+        self.by_street().river
+    }
+
+    /// The cards grouped by the street they were dealt on
+    #[must_use]
+    pub fn by_street(&self) -> BoardByStreet {
+        BoardByStreet::from(&self.cards[..])
+    }
+
+    #[must_use]
+    pub fn cards(&self) -> &CardsDynamic {
+        &self.cards
+    }
+}
+
+impl Deref for Board {
+    type Target = CardsDynamic;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cards
+    }
+}
+
+impl TryFrom<CardsDynamic> for Board {
+    type Error = PoksError;
+
+    fn try_from(cards: CardsDynamic) -> Result<Self> {
+        let mut board = Self::new();
+        for card in cards.iter() {
+            board.push(*card)?;
+        }
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CU;
+    use crate::errors::PoksError;
+    use crate::game::{Board, Game, Phase, test_seats};
+
+    #[test]
+    fn test_board_streets() {
+        let seats = test_seats(3, CU!(5000));
+        let mut game = Game::build(&seats, 0).unwrap();
+        assert_eq!(game.community_cards().phase(), Phase::Preflop);
+        assert_eq!(game.community_cards().flop(), None);
+
+        game.advance_phase().unwrap();
+        let board = game.community_cards();
+        assert_eq!(board.phase(), Phase::Flop);
+        assert_eq!(board.flop().unwrap()[..], board[..3]);
+        assert_eq!(board.turn(), None);
+
+        game.advance_phase().unwrap();
+        let board = game.community_cards();
+        assert_eq!(board.phase(), Phase::Turn);
+        assert_eq!(board.turn(), Some(board[3]));
+        assert_eq!(board.river(), None);
+
+        game.advance_phase().unwrap();
+        let mut board = game.community_cards().clone();
+        assert_eq!(board.phase(), Phase::River);
+        assert!(board.river().is_some());
+        let card = board[0];
+        assert!(matches!(
+            board.push(card),
+            Err(PoksError::BoardFull {
+                max: Board::MAX_CARDS
+            })
+        ));
+        assert_eq!(board.len(), Board::MAX_CARDS);
+    }
+}
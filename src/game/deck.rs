@@ -0,0 +1,113 @@
+//! The cards that were not dealt yet
+
+use std::ops::Deref;
+
+use poker::Card;
+
+use crate::Result;
+use crate::game::{Cards, CardsDynamic};
+
+/// The undealt cards of a game
+///
+/// Cards are only ever taken from the top of the deck, which is the end of the slice. Outside
+/// of this crate, the deck can be looked at but not changed, so a running game can not be
+/// corrupted by dealing cards twice or stacking the deck:
+///
+/// ```compile_fail
+/// fn stack(game: &mut poksen::game::Game) {
+///     game.deck().draw();
+/// }
+/// ```
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Deck {
+    cards: CardsDynamic,
+}
+
+impl Deck {
+    /// A deck that deals `cards` from the last to the first
+    pub(crate) fn new(cards: CardsDynamic) -> Self {
+        Self { cards }
+    }
+
+    /// Take the top card, if there is any left
+    pub(crate) fn draw(&mut self) -> Option<Card> {
+        self.cards.pop()
+    }
+
+    /// Take the top `N` cards, see [`CardsDynamic::deal`]
+    pub(crate) fn deal<const N: usize>(&mut self) -> Result<Cards<N>> {
+        self.cards.deal()
+    }
+
+    /// The undealt cards, the top card is the last one
+    #[must_use]
+    pub fn cards(&self) -> &CardsDynamic {
+        &self.cards
+    }
+}
+
+impl Deref for Deck {
+    type Target = [Card];
+
+    fn deref(&self) -> &Self::Target {
+        &self.cards
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use poker::Card;
+
+    use crate::CU;
+    use crate::errors::PoksError;
+    use crate::game::deck::Deck;
+    use crate::game::{Cards, CardsDynamic, Game, full_deck, test_seats};
+
+    #[test]
+    fn test_draw_from_top() {
+        let cards = full_deck();
+        let mut deck = Deck::new(cards.clone());
+        assert_eq!(deck.draw(), cards.last().copied());
+        assert_eq!(deck.len(), 51);
+        assert_eq!(&deck[..], &cards[..51]);
+    }
+
+    #[test]
+    fn test_deal() {
+        let cards = full_deck();
+        let mut deck = Deck::new(cards.clone());
+        let hand: Cards<2> = deck.deal().unwrap();
+        assert_eq!(hand, [cards[51], cards[50]]);
+        assert_eq!(&deck[..], &cards[..50]);
+
+        let mut empty = CardsDynamic::new();
+        assert!(matches!(
+            empty.deal::<2>(),
+            Err(PoksError::InsufficientCards)
+        ));
+        let mut one: CardsDynamic = cards[..1].into();
+        assert!(one.deal::<2>().is_err());
+        assert_eq!(one.len(), 1);
+    }
+
+    #[test]
+    fn test_deck_is_only_dealt_from() {
+        let seats = test_seats(3, CU!(5000));
+        let mut game = Game::build(&seats, 0).unwrap();
+        let before: Vec<Card> = game.deck().to_vec();
+
+        // a copy of the deck can not reach the live deck
+        let mut copy = game.deck().cards().clone();
+        copy.clear();
+        assert_eq!(game.deck().len(), before.len());
+
+        game.check_down_to_showdown().unwrap();
+        // burn and board cards were taken from the top, the rest is untouched
+        assert_eq!(&game.deck()[..], &before[..before.len() - 8]);
+    }
+}
@@ -0,0 +1,219 @@
+//! Monte Carlo estimation of hand equities
+//!
+//! Bots can use [`estimate`] to judge how good their hand is against unknown hands.
+
+use poker::{Card, Eval, FiveCard};
+use rand::seq::SliceRandom;
+
+use crate::Result;
+use crate::errors::PoksError;
+use crate::game::{Cards, DeckKind, RNG, evaluator, remaining_deck};
+
+/// The hole cards plus the complete board, without allocating
+fn seven(hand: &Cards<2>, board: &[Card], runout: &[Card]) -> Cards<7> {
+    let mut cards = hand.iter().chain(board).chain(runout);
+    std::array::from_fn(|_| *cards.next().expect("the board and runout are five cards"))
+}
+
+/// Without a single sample, there is nothing to estimate
+fn check_iters(iters: usize) -> Result<()> {
+    if iters == 0 {
+        return Err(PoksError::ConfigError {
+            field: "iters".to_string(),
+            reason: "at least one sample is needed".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Estimate the share of the pot each of the given hands wins once the board is complete
+///
+/// `hands` are the hole cards of all players still in the hand, `board` holds the community
+/// cards dealt so far. The missing board cards are sampled `iters` times from all cards of a
+/// `deck_kind` deck that are not known. A tie counts as a fraction of a win for each of the tied
+/// hands.
+///
+/// # Errors
+///
+/// Returns [`PoksError::ConfigError`] if `iters` is zero,
+/// [`PoksError::CardEvaluationError`] if the board has more than 5 cards, and
+/// [`PoksError::DuplicateCard`] if a card is in the hands or on the board more than once.
+pub(crate) fn hand_equities(
+    hands: &[Cards<2>],
+    board: &[Card],
+    deck_kind: DeckKind,
+    iters: usize,
+    rng: &mut RNG,
+) -> Result<Vec<f64>> {
+    check_iters(iters)?;
+    if board.len() > 5 {
+        return Err(PoksError::card_evaluation(format!(
+            "a board has at most 5 cards, got {}",
+            board.len()
+        )));
+    }
+    let mut known: Vec<Card> = hands.iter().flatten().copied().collect();
+    known.extend_from_slice(board);
+    let unknown = remaining_deck(deck_kind, &known)?;
+    let missing = 5 - board.len();
+
+    let evaluator = evaluator();
+    let mut shares = vec![0.0; hands.len()];
+    let mut evals: Vec<Eval<FiveCard>> = Vec::with_capacity(hands.len());
+    for _ in 0..iters {
+        let runout: Vec<Card> = unknown.choose_multiple(rng, missing).copied().collect();
+        evals.clear();
+        for hand in hands {
+            evals.push(
+                evaluator
+                    .evaluate_five(seven(hand, board, &runout))
+                    .map_err(|e| PoksError::card_evaluation(format!("{e:?}")))?,
+            );
+        }
+        let best = match evals.iter().max() {
+            Some(best) => *best,
+            None => break, // no hands given
+        };
+        let winners = evals.iter().filter(|e| **e == best).count();
+        for (share, eval) in shares.iter_mut().zip(&evals) {
+            if *eval == best {
+                *share += 1.0 / winners as f64;
+            }
+        }
+    }
+
+    Ok(shares.into_iter().map(|s| s / iters as f64).collect())
+}
+
+/// Estimate how often `hand` wins or ties against `opponents` unknown hands
+///
+/// The hands of the opponents and the missing board cards are sampled `iters` times from the
+/// cards of a `deck_kind` deck that are neither in `hand` nor on the `board`. The result is the
+/// fraction of samples in which no opponent has a better hand.
+///
+/// # Errors
+///
+/// Returns [`PoksError::ConfigError`] if `iters` is zero,
+/// [`PoksError::CardEvaluationError`] if the board has more than 5 cards,
+/// [`PoksError::DuplicateCard`] if a card is in the hand or on the board more than once, and
+/// [`PoksError::InsufficientCards`] if there are not enough cards left to deal the opponents.
+pub fn estimate(
+    hand: Cards<2>,
+    board: &[Card],
+    opponents: usize,
+    deck_kind: DeckKind,
+    iters: usize,
+    rng: &mut RNG,
+) -> Result<f64> {
+    check_iters(iters)?;
+    if board.len() > 5 {
+        return Err(PoksError::card_evaluation(format!(
+            "a board has at most 5 cards, got {}",
+            board.len()
+        )));
+    }
+    let mut known = hand.to_vec();
+    known.extend_from_slice(board);
+    let unknown = remaining_deck(deck_kind, &known)?;
+    let missing = 5 - board.len();
+    let needed = 2 * opponents + missing;
+    if needed > unknown.len() {
+        return Err(PoksError::InsufficientCards);
+    }
+
+    let evaluator = evaluator();
+    let evaluate = |cards: Cards<7>| {
+        evaluator
+            .evaluate_five(cards)
+            .map_err(|e| PoksError::card_evaluation(format!("{e:?}")))
+    };
+    let mut wins = 0usize;
+    for _ in 0..iters {
+        let sample: Vec<Card> = unknown.choose_multiple(rng, needed).copied().collect();
+        let (runout, opponent_cards) = sample.split_at(missing);
+
+        let hero = evaluate(seven(&hand, board, runout))?;
+        let mut beaten = false;
+        for opponent in opponent_cards.chunks(2) {
+            let opponent = [opponent[0], opponent[1]];
+            if evaluate(seven(&opponent, board, runout))? > hero {
+                beaten = true;
+                break;
+            }
+        }
+        if !beaten {
+            wins += 1;
+        }
+    }
+
+    Ok(wins as f64 / iters as f64)
+}
+
+#[cfg(test)]
+mod test {
+    use poker::Card;
+    use rand::SeedableRng;
+
+    use crate::errors::PoksError;
+    use crate::game::equity::{estimate, hand_equities};
+    use crate::game::{DeckKind, RNG};
+
+    fn cards(s: &str) -> Vec<Card> {
+        s.split_whitespace().map(|c| c.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_estimate_pocket_aces() {
+        let mut rng = RNG::from_seed([5; 32]);
+        let aces = ["As".parse().unwrap(), "Ah".parse().unwrap()];
+        let equity = estimate(aces, &[], 1, DeckKind::Standard, 5000, &mut rng).unwrap();
+        assert!(equity > 0.8, "{equity}");
+
+        // more opponents make it harder to win
+        let crowded = estimate(aces, &[], 5, DeckKind::Standard, 2000, &mut rng).unwrap();
+        assert!(crowded < equity);
+    }
+
+    #[test]
+    fn test_estimate_known_cards() {
+        let mut rng = RNG::from_seed([6; 32]);
+        // a royal flush on the board can not be beaten
+        let board = cards("Ts Js Qs Ks As");
+        let hand = ["2c".parse().unwrap(), "3d".parse().unwrap()];
+        assert_eq!(
+            estimate(hand, &board, 3, DeckKind::Standard, 200, &mut rng).unwrap(),
+            1.0
+        );
+
+        assert!(matches!(
+            estimate(hand, &board, 30, DeckKind::Standard, 10, &mut rng),
+            Err(PoksError::InsufficientCards)
+        ));
+        assert!(
+            estimate(
+                hand,
+                &cards("2s 3s 4s 5s 6s 7s"),
+                1,
+                DeckKind::Standard,
+                10,
+                &mut rng
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_zero_iterations() {
+        let mut rng = RNG::from_seed([7; 32]);
+        let aces = ["As".parse().unwrap(), "Ah".parse().unwrap()];
+        let kings = ["Ks".parse().unwrap(), "Kh".parse().unwrap()];
+        assert!(matches!(
+            estimate(aces, &[], 1, DeckKind::Standard, 0, &mut rng),
+            Err(PoksError::ConfigError { .. })
+        ));
+        assert!(matches!(
+            hand_equities(&[aces, kings], &[], DeckKind::Standard, 0, &mut rng),
+            Err(PoksError::ConfigError { .. })
+        ));
+    }
+}
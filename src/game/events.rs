@@ -0,0 +1,24 @@
+//! Typed events of a game, as an alternative to parsing the game log
+
+use crate::currency::Currency;
+use crate::game::{Action, CardsDynamic, Phase, PlayerID, Winner};
+
+/// Something that happened in a game
+///
+/// The events of a game are available in order through
+/// [`Game::events`](crate::game::Game::events).
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum GameEvent {
+    /// A player posted a blind of this amount
+    BlindPosted(PlayerID, Currency),
+    /// A player took an action
+    Acted(PlayerID, Action),
+    /// A new street begins
+    PhaseChanged(Phase),
+    /// Community cards were dealt to the table
+    CardsDealt(CardsDynamic),
+    /// The remaining hands are compared
+    Showdown,
+    /// A pot was won
+    Winner(Winner),
+}
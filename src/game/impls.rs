@@ -1,13 +1,20 @@
 use std::{
+    collections::HashSet,
     fmt::Display,
     ops::{Deref, DerefMut},
+    str::FromStr,
 };
 
 use poker::Card;
+use rand::seq::SliceRandom;
 
 use crate::{
-    CU,
-    game::{Action, Cards, CardsDynamic, Phase, PlayerState, Winner, show_eval_cards},
+    Result,
+    errors::PoksError,
+    game::{
+        Action, BoardByStreet, Cards, CardsDynamic, Phase, PlayerState, RNG, Winner,
+        show_eval_cards,
+    },
     len_to_const_arr,
 };
 
@@ -17,10 +24,84 @@ impl CardsDynamic {
     }
 
     pub fn try_static<const N: usize>(self) -> Option<Cards<N>> {
+        self.try_static_result().ok()
+    }
+
+    /// Like [`try_static`](Self::try_static), but tells how many cards there were
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::CardCountMismatch`] if there are not exactly `N` cards.
+    pub fn try_static_result<const N: usize>(self) -> Result<Cards<N>> {
+        let mismatch = PoksError::CardCountMismatch {
+            expected: N,
+            actual: self.len(),
+        };
         if N != self.len() {
-            return None;
+            return Err(mismatch);
+        }
+        len_to_const_arr(&self.inner).map_err(|_| mismatch)
+    }
+
+    /// Check whether any card is contained more than once
+    pub fn has_duplicates(&self) -> bool {
+        self.validate_unique().is_err()
+    }
+
+    /// Make sure that no card is contained more than once
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::DuplicateCard`] for the first card that is seen a second time.
+    pub fn validate_unique(&self) -> Result<()> {
+        let mut seen = HashSet::with_capacity(self.len());
+        for card in self.iter() {
+            if !seen.insert(card) {
+                return Err(PoksError::DuplicateCard { card: *card });
+            }
+        }
+        Ok(())
+    }
+
+    /// Take `N` cards from the end, the last card comes first
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::InsufficientCards`] if there are less than `N` cards, no card is
+    /// taken then.
+    pub fn deal<const N: usize>(&mut self) -> Result<Cards<N>> {
+        if self.len() < N {
+            return Err(PoksError::InsufficientCards);
+        }
+        Ok(std::array::from_fn(|_| {
+            self.inner.pop().expect("the number of cards was checked")
+        }))
+    }
+
+    /// Bring the cards into a random order
+    pub fn shuffle(&mut self, rng: &mut RNG) {
+        self.inner.shuffle(rng);
+    }
+}
+
+impl BoardByStreet {
+    /// All dealt community cards, in the order they were dealt
+    pub fn cards(&self) -> Vec<Card> {
+        let mut cards: Vec<Card> = self.flop.map(Vec::from).unwrap_or_default();
+        cards.extend(self.turn);
+        cards.extend(self.river);
+        cards
+    }
+}
+
+impl From<&[Card]> for BoardByStreet {
+    /// Group a board by the order of the cards, the first three are the flop
+    fn from(board: &[Card]) -> Self {
+        Self {
+            flop: board.get(..3).and_then(|flop| flop.try_into().ok()),
+            turn: board.get(3).copied(),
+            river: board.get(4).copied(),
         }
-        len_to_const_arr(&self.inner).ok()
     }
 }
 
@@ -30,6 +111,25 @@ impl Display for Phase {
     }
 }
 
+impl FromStr for Phase {
+    type Err = PoksError;
+
+    /// Parse the names produced by [`Phase::as_str`]
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(match s {
+            "preflop" => Phase::Preflop,
+            "flop" => Phase::Flop,
+            "turn" => Phase::Turn,
+            "river" => Phase::River,
+            _ => {
+                return Err(PoksError::InvalidPhase {
+                    input: s.to_string(),
+                });
+            }
+        })
+    }
+}
+
 impl Display for PlayerState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{self:?}")
@@ -43,7 +143,7 @@ impl Display for Action {
             "{}",
             match self {
                 Action::Fold => "folds".to_string(),
-                Action::Call(bet) if *bet == CU!(0) => "checks".to_string(),
+                Action::Check => "checks".to_string(),
                 Action::Call(bet) => format!("calls for {bet}"),
                 Action::Raise(bet) => format!("raises by {bet}"),
                 Action::AllIn(bet) => format!("goes all in! ({bet})"),
@@ -65,6 +165,13 @@ impl Display for Winner {
                     )
                 }
                 Self::UnknownCards(pot, pid) => format!("Player {pid} won {pot}."),
+                Self::Split(shares, eval) => {
+                    let shares: Vec<String> = shares
+                        .iter()
+                        .map(|(pid, amount, _)| format!("Player {pid} won {amount}"))
+                        .collect();
+                    format!("Split pot with {eval}: {}.", shares.join(", "))
+                }
             }
         )
     }
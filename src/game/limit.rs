@@ -0,0 +1,180 @@
+//! How much players may bet and raise
+
+use crate::Result;
+use crate::currency::Currency;
+use crate::errors::PoksError;
+use crate::game::{Game, Phase, PlayerID};
+
+/// The betting structure of a game
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BettingLimit {
+    /// Players may bet anything up to their whole stack
+    #[default]
+    NoLimit,
+    /// A raise may be at most as big as the pot after calling
+    PotLimit,
+    /// Bets and raises are exactly `small_bet` before the flop and on the flop, and `big_bet` on
+    /// the turn and river. Each street is capped at [`BettingLimit::FIXED_LIMIT_CAP`] bets.
+    FixedLimit {
+        small_bet: Currency,
+        big_bet: Currency,
+    },
+}
+
+impl BettingLimit {
+    /// How many bets a street of a fixed limit game can have, the opening bet included
+    ///
+    /// Before the flop, the big blind counts as the opening bet.
+    pub const FIXED_LIMIT_CAP: u8 = 4;
+
+    /// The smallest bet that opens a street of `phase`
+    #[must_use]
+    pub fn opening_bet(&self, phase: Phase, big_blind: Currency) -> Currency {
+        match self {
+            BettingLimit::NoLimit | BettingLimit::PotLimit => big_blind,
+            BettingLimit::FixedLimit { small_bet, big_bet } => match phase {
+                Phase::Preflop | Phase::Flop => *small_bet,
+                Phase::Turn | Phase::River => *big_bet,
+            },
+        }
+    }
+}
+
+impl Game {
+    #[must_use]
+    pub fn betting_limit(&self) -> BettingLimit {
+        self.limit
+    }
+
+    /// Play this game with another betting structure
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::ConfigError`] if a player already acted in this game.
+    pub fn set_betting_limit(&mut self, limit: BettingLimit) -> Result<()> {
+        if !self.action_history.is_empty() {
+            return Err(PoksError::ConfigError {
+                field: "betting limit".to_string(),
+                reason: "players already acted in this game".to_string(),
+            });
+        }
+        self.limit = limit;
+        self.min_raise = limit.opening_bet(self.phase, self.big_blind);
+        Ok(())
+    }
+
+    /// The largest amount the player whose turn it is may raise by, [`None`] if there is no
+    /// limit
+    ///
+    /// Like [`Game::min_raise_amount`], this is what is added on top of the highest bet of the
+    /// round, the call is not included.
+    #[must_use]
+    pub fn max_raise_amount(&self) -> Option<Currency> {
+        self.max_raise_for(self.turn)
+    }
+
+    pub(crate) fn max_raise_for(&self, pid: PlayerID) -> Option<Currency> {
+        match self.limit {
+            BettingLimit::NoLimit => None,
+            BettingLimit::PotLimit => {
+                let to_call = self
+                    .highest_bet_of_round()
+                    .saturating_sub(self.players[pid].round_bet);
+                Some(self.pot() + to_call)
+            }
+            BettingLimit::FixedLimit { .. } => {
+                Some(self.limit.opening_bet(self.phase, self.big_blind))
+            }
+        }
+    }
+
+    /// Whether the current street reached the cap of a fixed limit game
+    #[must_use]
+    pub fn raise_cap_reached(&self) -> bool {
+        matches!(self.limit, BettingLimit::FixedLimit { .. })
+            && self.bets_this_street >= BettingLimit::FIXED_LIMIT_CAP
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CU;
+    use crate::errors::PoksError;
+    use crate::game::{Action, BettingLimit, Game, GameState, Phase, test_seats};
+
+    fn game(limit: BettingLimit) -> Game {
+        let seats = test_seats(3, CU!(5000));
+        let mut game = Game::build(&seats, 0).unwrap();
+        game.set_betting_limit(limit).unwrap();
+        game
+    }
+
+    #[test]
+    fn test_pot_limit_raise_cap() {
+        let mut game = game(BettingLimit::PotLimit);
+        // the pot holds the blinds of 1,50, calling the big blind makes it 2,50
+        assert_eq!(game.max_raise_amount(), Some(CU!(2, 50)));
+        assert!(matches!(
+            game.validate_action(0, Action::Raise(CU!(3, 51))),
+            Err(PoksError::TooHighBetAmount { .. })
+        ));
+        assert!(matches!(
+            game.validate_action(0, Action::AllIn(CU!(5000))),
+            Err(PoksError::TooHighBetAmount { .. })
+        ));
+        assert!(!game.valid_actions().contains(&Action::AllIn(CU!(5000))));
+        game.process_action(Some(Action::Raise(CU!(3, 50))))
+            .unwrap();
+        assert_eq!(game.highest_bet_of_round(), CU!(3, 50));
+        // 5 in the pot and 3 to call
+        assert_eq!(game.max_raise_amount(), Some(CU!(8)));
+    }
+
+    #[test]
+    fn test_fixed_limit_cap_of_four() {
+        let limit = BettingLimit::FixedLimit {
+            small_bet: CU!(1),
+            big_bet: CU!(2),
+        };
+        let mut game = game(limit);
+        while game.phase() == Phase::Preflop {
+            let call = game.action_call();
+            game.process_action(Some(call)).unwrap();
+        }
+        assert_eq!(game.min_raise_amount(), CU!(1));
+        assert_eq!(game.max_raise_amount(), Some(CU!(1)));
+        assert!(matches!(
+            game.validate_action(game.turn(), Action::Raise(CU!(2))),
+            Err(PoksError::TooHighBetAmount { .. })
+        ));
+
+        // a bet and three raises
+        for bet in 1..=4 {
+            assert_eq!(game.state(), GameState::RaiseAllowed);
+            let raise = game.action_call().chips_required(&game, game.turn()) + CU!(1);
+            game.process_action(Some(Action::Raise(raise))).unwrap();
+            assert_eq!(game.highest_bet_of_round(), CU!(bet));
+        }
+        assert!(game.raise_cap_reached());
+        assert_eq!(game.state(), GameState::RaiseDisallowed);
+        assert!(
+            !game
+                .valid_actions()
+                .iter()
+                .any(|a| matches!(a, Action::Raise(_)))
+        );
+        assert!(matches!(
+            game.process_action(Some(Action::Raise(CU!(4)))),
+            Err(PoksError::RaiseNotAllowed)
+        ));
+
+        while game.phase() == Phase::Flop {
+            let call = game.action_call();
+            game.process_action(Some(call)).unwrap();
+        }
+        // the cap is per street, and the bets double on the turn
+        assert!(!game.raise_cap_reached());
+        assert_eq!(game.max_raise_amount(), Some(CU!(2)));
+    }
+}
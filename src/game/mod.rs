@@ -4,42 +4,93 @@ use std::sync::OnceLock;
 use poker::evaluate::FiveCardHandClass;
 use poker::{Card, Eval, Evaluator, FiveCard, Rank, Suit};
 use rand::prelude::*;
-use tracing::{debug, info, trace};
+use tracing::{debug, debug_span, info, trace};
 
-use crate::currency::Currency;
+use crate::currency::{Currency, FormatOpts};
 use crate::errors::PoksError;
-use crate::lobby::Seat;
-use crate::players::PlayerState;
-use crate::{CU, Result, err_int};
-
+use crate::lobby::{RakeConfig, Seat};
+use crate::players::{PlayerBasicFields, PlayerBehavior, PlayerLocal, PlayerState};
+use crate::{Result, err_int};
+
+mod betting;
+mod board;
+mod deck;
+pub mod equity;
+mod events;
 mod impls; // additional trait impls
+mod limit;
+mod perspective;
+mod phase;
+mod pot;
+mod replay;
+mod runout;
+#[cfg(feature = "serde")]
+mod snapshot;
+
+pub use board::Board;
+pub use deck::Deck;
+pub use events::GameEvent;
+pub use limit::BettingLimit;
+pub use perspective::{InfoSet, PlayerView};
+pub use phase::Phase;
+pub use pot::Pot;
+pub use replay::Replay;
 
 pub type PlayerID = usize;
 pub type Cards<const N: usize> = [Card; N];
 pub type GlogItem = (Option<PlayerID>, String);
-pub type RNG = rand::rngs::StdRng;
+pub type RNG = rand_chacha::ChaCha12Rng;
 pub type Seed = <RNG as rand::SeedableRng>::Seed;
 
 pub static EVALUATOR: OnceLock<Evaluator> = OnceLock::new();
+/// The small blind of games that are built without blinds
+pub const DEFAULT_SMALL_BLIND: Currency = Currency::new(0, 50);
+/// The big blind of games that are built without blinds
+pub const DEFAULT_BIG_BLIND: Currency = Currency::new(1, 0);
+/// How many board runouts [`Game::all_in_equities`] samples
+pub const ALL_IN_EQUITY_ITERATIONS: usize = 5000;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub struct CardsDynamic {
     inner: Vec<Card>,
 }
 
+/// The community cards, grouped by the street they were dealt on
+///
+/// Streets that were not dealt yet are [`None`].
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct BoardByStreet {
+    pub flop: Option<Cards<3>>,
+    pub turn: Option<Card>,
+    pub river: Option<Card>,
+}
+
+/// The cards a game is played with
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
-pub enum Phase {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DeckKind {
+    /// All 52 cards
     #[default]
-    Preflop,
-    Flop,
-    Turn,
-    River,
+    Standard,
+    /// The 36 cards from six to ace
+    ///
+    /// Only the deck is changed, hands are still ranked like in regular Texas Hold'em.
+    ShortDeck,
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+/// Who won a pot
+///
+/// The derived ordering compares the variants first and is only meant for collections. The
+/// winners of a hand are ordered from the main pot to the last side pot, use
+/// [`Winner::cmp_by_amount`] to order them by what they won instead.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Winner {
     UnknownCards(Currency, PlayerID),
     KnownCards(Currency, PlayerID, Eval<FiveCard>, Cards<7>),
+    /// Equally strong hands split the pot, starting with the first player left of the dealer
+    ///
+    /// Every share holds the cards the player made their hand with.
+    Split(Vec<(PlayerID, Currency, Cards<7>)>, Eval<FiveCard>),
 }
 
 #[derive(Debug, Clone)]
@@ -47,35 +98,79 @@ pub struct Player {
     state: PlayerState,
     total_bet: Currency,
     round_bet: Currency,
+    acted: bool,
+    can_raise: bool,
     seat: Seat,
 }
 
+/// With the `serde` feature, a game can be saved as a snapshot, see the `snapshot` module
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "snapshot::GameRepr", try_from = "snapshot::GameRepr")
+)]
 pub struct Game {
     phase: Phase,
     turn: PlayerID,
     dealer: PlayerID,
     players: Vec<Player>,
-    community_cards: CardsDynamic,
-    winner: Option<Winner>,
-    deck: CardsDynamic,
+    community_cards: Board,
+    winners: Vec<Winner>,
+    deck: Deck,
+    burned: CardsDynamic,
     state: GameState,
     small_blind: Currency,
     big_blind: Currency,
     game_log: Vec<GlogItem>,
+    verbose_log: bool,
+    events: Vec<GameEvent>,
+    rake: Option<RakeConfig>,
+    rake_taken: Currency,
+    run_it: u8,
+    extra_runouts: Vec<CardsDynamic>,
+    dead_blinds: Vec<PlayerID>,
+    limit: BettingLimit,
+    bets_this_street: u8,
+    deck_kind: DeckKind,
+    last_action: Option<(PlayerID, Action)>,
+    last_aggressor: Option<PlayerID>,
+    min_raise: Currency,
+    action_history: Vec<(PlayerID, Action)>,
     seed: Seed,
     rng: RNG,
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+/// What a player does on their turn
+///
+/// Actions are not ordered, compare them with [`Action::aggression_rank`] or
+/// [`Action::committed_amount`] instead.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Action {
     Fold,
+    /// Pass without betting, only possible if nobody bet more than the player this round
+    Check,
     Call(Currency),
     Raise(Currency),
     AllIn(Currency),
 }
 
+/// What [`Game::process_action`] did with an action
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ActionOutcome {
+    /// The player whose turn it is has not decided yet
+    AwaitingAction,
+    /// The action was applied to the game
+    Applied,
+    /// The player whose turn it was can not act anymore and was skipped
+    Skipped,
+    /// The hand is finished
+    Finished,
+}
+
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum GameState {
     #[default]
@@ -128,45 +223,229 @@ impl Game {
         seed
     }
 
+    /// The seed this game was dealt from
+    #[must_use]
+    pub fn current_seed(&self) -> Seed {
+        self.seed
+    }
+
+    /// A random number generator for the next decision of the player `pid`
+    ///
+    /// It is derived from the seed of this game, the seat and the number of actions so far, so
+    /// replaying a seeded game replays the decisions of computer players as well, while every
+    /// decision still gets its own random numbers.
+    #[must_use]
+    pub fn player_rng(&self, pid: PlayerID) -> RNG {
+        let mut seed = self.seed;
+        let mix = (pid as u64 + 1)
+            .to_le_bytes()
+            .into_iter()
+            .chain((self.action_history.len() as u64).to_le_bytes());
+        for (byte, m) in seed.iter_mut().rev().zip(mix) {
+            *byte ^= m;
+        }
+        RNG::from_seed(seed)
+    }
+
+    /// Write a seed as 64 hex digits, the format read by [`Game::from_seed_str`]
+    #[must_use]
+    pub fn seed_to_hex(seed: &Seed) -> String {
+        seed.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    /// Read a seed from 64 hex digits, so a hand can be reproduced from a pasted seed
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::InvalidSeed`] if the input is not exactly 64 hex digits.
+    pub fn from_seed_str(s: &str) -> Result<Seed> {
+        let s = s.trim();
+        if s.len() != 64 || !s.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(PoksError::InvalidSeed {
+                input: s.to_string(),
+            });
+        }
+        let mut seed = Seed::default();
+        for (byte, i) in seed.iter_mut().zip((0..s.len()).step_by(2)) {
+            *byte = u8::from_str_radix(&s[i..i + 2], 16).expect("hex digits were checked");
+        }
+        Ok(seed)
+    }
+
+    /// Clone this game, but with its own random number generator
+    ///
+    /// A plain [`Clone`] continues the random sequence of the original, so independent
+    /// simulations of the same game should each get a different `rng`. The clone still plays with
+    /// the same seats as the original.
+    #[must_use]
+    pub fn clone_with_rng(&self, rng: RNG) -> Game {
+        Game {
+            rng,
+            ..self.clone()
+        }
+    }
+
+    /// Clone this game for a hypothetical rollout that can not affect the live game
+    ///
+    /// Unlike [`Game::clone_with_rng`], the clone gets a random number generator and seed of its
+    /// own, derived from `seed`, and the undealt cards are shuffled with it. Every player sits on
+    /// a new seat with a copy of their hand and currency, so dealing, betting and payouts on the
+    /// clone leave the original game, its seats and the cards it will deal untouched.
+    ///
+    /// The seat of the player whose turn it is is locked while they
+    /// [`act`](PlayerBehavior::act), so a player simulating from inside `act` passes their own
+    /// fields as `me`.
+    ///
+    /// Simulation clones must never be fed back into a [`Lobby`](crate::lobby::Lobby) or used in
+    /// place of the live game, their deck and seats have nothing to do with the real ones.
+    #[must_use]
+    pub fn clone_for_simulation(&self, seed: Seed, me: Option<&PlayerBasicFields>) -> Game {
+        let mut rng = RNG::from_seed(seed);
+        let mut cards = self.deck.cards().clone();
+        cards.shuffle(&mut rng);
+        let players = self
+            .players
+            .iter()
+            .enumerate()
+            .map(|(pid, player)| {
+                let fields = match me {
+                    Some(me) if pid == self.turn => me.clone(),
+                    _ => {
+                        let behavior = player.seat.behavior();
+                        PlayerBasicFields {
+                            hand: *behavior.hand(),
+                            currency: *behavior.currency(),
+                        }
+                    }
+                };
+                let mut behavior = PlayerLocal::new();
+                *behavior.hand_mut() = fields.hand;
+                behavior.set_currency(fields.currency);
+                Player {
+                    seat: Seat::new(Box::new(behavior)),
+                    ..player.clone()
+                }
+            })
+            .collect();
+        Game {
+            players,
+            deck: Deck::new(cards),
+            seed,
+            rng,
+            ..self.clone()
+        }
+    }
+
+    /// The random number generator of this game, for simulations on clones of it
+    pub fn rng_mut(&mut self) -> &mut RNG {
+        &mut self.rng
+    }
+
     pub fn buid_with_seed(seats: &[Seat], dealer_pos: PlayerID, seed: Seed) -> Result<Self> {
+        Self::build_with_deck_and_seed(seats, dealer_pos, DeckKind::default(), seed)
+    }
+
+    pub fn build_with_deck_and_seed(
+        seats: &[Seat],
+        dealer_pos: PlayerID,
+        deck_kind: DeckKind,
+        seed: Seed,
+    ) -> Result<Self> {
+        Self::build_with_blinds(
+            seats,
+            dealer_pos,
+            deck_kind,
+            seed,
+            DEFAULT_SMALL_BLIND,
+            DEFAULT_BIG_BLIND,
+        )
+    }
+
+    /// Build a game whose blinds are `small_blind` and `big_blind`
+    pub fn build_with_blinds(
+        seats: &[Seat],
+        dealer_pos: PlayerID,
+        deck_kind: DeckKind,
+        seed: Seed,
+        small_blind: Currency,
+        big_blind: Currency,
+    ) -> Result<Self> {
         trace!("Building a new game");
         assert!(seats.len() >= 2);
         let mut rng = RNG::from_seed(seed);
-        let mut deck: CardsDynamic = poker::deck::shuffled_with(&mut rng).into();
-        if seats.len() > deck.len() / 2 {
-            // TODO: return a proper error and result
-            panic!("Not enough cards in a deck for this many players!")
+        let mut deck = Deck::new(deck_kind.shuffled(&mut rng));
+        // besides the hands, five cards go to the table and three are burned
+        let max_players = (deck.len() - 8) / 2;
+        if seats.len() > max_players {
+            return Err(PoksError::too_many_players(seats.len(), max_players));
         }
         let mut players = Vec::new();
         for seat in seats {
-            let hand: Cards<2> = [deck.pop().unwrap(), deck.pop().unwrap()];
-            players.push(Player::new(hand, seat.clone()));
+            let hand: Cards<2> = deck.deal()?;
+            let mut player = Player::new(hand, seat.clone());
+            if seat.currency() == Currency::ZERO {
+                // eliminated players are not dealt into the hand
+                player.state = PlayerState::Lost;
+            }
+            players.push(player);
         }
         let mut game = Game {
             turn: 0,
             phase: Phase::default(),
             players,
-            community_cards: CardsDynamic::new(),
-            winner: None,
+            community_cards: Board::new(),
+            winners: Vec::new(),
             deck,
+            burned: CardsDynamic::new(),
             state: GameState::default(),
-            small_blind: CU!(0, 50),
-            big_blind: CU!(1),
+            small_blind,
+            big_blind,
             dealer: dealer_pos,
             game_log: Vec::with_capacity(32),
+            verbose_log: false,
+            events: Vec::new(),
+            rake: None,
+            rake_taken: Currency::ZERO,
+            run_it: 1,
+            extra_runouts: Vec::new(),
+            dead_blinds: Vec::new(),
+            limit: BettingLimit::default(),
+            bets_this_street: 0,
+            deck_kind,
+            last_action: None,
+            last_aggressor: None,
+            min_raise: big_blind,
+            action_history: Vec::new(),
             rng,
             seed,
         };
 
+        #[cfg(debug_assertions)]
+        game.assert_deck_integrity();
         game.post_blinds()?;
+        game.start_betting();
 
         trace!("New game is ready");
         Ok(game)
     }
 
     pub fn build(seats: &[Seat], dealer_pos: PlayerID) -> Result<Self> {
+        Self::build_with_deck(seats, dealer_pos, DeckKind::default())
+    }
+
+    pub fn build_with_deck(
+        seats: &[Seat],
+        dealer_pos: PlayerID,
+        deck_kind: DeckKind,
+    ) -> Result<Self> {
         let seed = Self::seed();
-        Self::buid_with_seed(seats, dealer_pos, seed)
+        Self::build_with_deck_and_seed(seats, dealer_pos, deck_kind, seed)
+    }
+
+    /// The kind of deck this game is played with
+    #[must_use]
+    pub fn deck_kind(&self) -> DeckKind {
+        self.deck_kind
     }
 
     #[must_use]
@@ -186,12 +465,62 @@ impl Game {
         }
         self.phase = phase;
         glogf!(self, None, "Phase: {phase}");
+        self.events.push(GameEvent::PhaseChanged(phase));
+        self.start_betting();
     }
 
+    /// Everything that was bet in this hand, [`settled_pot`](Self::settled_pot) plus
+    /// [`current_street_bets`](Self::current_street_bets)
     #[must_use]
     pub fn pot(&self) -> Currency {
+        self.settled_pot() + self.current_street_bets()
+    }
+
+    /// The pot as it was when the current street started
+    #[must_use]
+    pub fn settled_pot(&self) -> Currency {
         debug_assert!(!self.players.is_empty());
-        self.players.iter().map(|p| p.total_bet + p.round_bet).sum()
+        self.players.iter().map(|p| p.total_bet).sum()
+    }
+
+    /// Everything that was bet on the current street
+    #[must_use]
+    pub fn current_street_bets(&self) -> Currency {
+        self.players.iter().map(|p| p.round_bet).sum()
+    }
+
+    /// Everything the player has put into the pot in this hand
+    #[must_use]
+    pub fn contributed_by(&self, pid: PlayerID) -> Currency {
+        self.players[pid].total_bet()
+    }
+
+    /// The part of a bet in this round that nobody matched, and who made it
+    ///
+    /// This is the difference between the highest and the second highest bet of the round,
+    /// including the bets of players who folded since. When everyone folds to a bet, this amount
+    /// belongs back to the player who made it.
+    #[must_use]
+    pub fn uncalled_amount(&self) -> Option<(PlayerID, Currency)> {
+        let (pid, highest) = self
+            .players
+            .iter()
+            .enumerate()
+            .map(|(pid, p)| (pid, p.round_bet))
+            .max_by_key(|(_, bet)| *bet)?;
+        let second = self
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != pid)
+            .map(|(_, p)| p.round_bet)
+            .max()
+            .unwrap_or(Currency::ZERO);
+        if highest > second {
+            Some((pid, highest - second))
+        } else {
+            None
+        }
     }
 
     #[must_use]
@@ -202,55 +531,115 @@ impl Game {
 
     #[must_use]
     pub fn is_finished(&self) -> bool {
-        self.winner.is_some()
+        !self.winners.is_empty()
     }
 
-    pub fn set_winner(&mut self, w: Winner) {
-        w.payout(self).expect("could not payout the winner");
-        self.winner = Some(w);
-        glog!(self, None, self.winner.unwrap().to_string())
+    /// Pay out the winner of a pot
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of [`Winner::payout`], the winner is not recorded then.
+    pub fn set_winner(&mut self, w: Winner) -> Result<()> {
+        w.payout(self)?;
+        glog!(self, None, w.to_string());
+        self.events.push(GameEvent::Winner(w.clone()));
+        self.winners.push(w);
+        Ok(())
     }
 
+    /// The winner of the main pot
     #[must_use]
     pub fn winner(&self) -> Option<Winner> {
-        self.winner
+        self.winners.first().cloned()
+    }
+
+    /// The winners of all pots, starting with the main pot
+    #[must_use]
+    pub fn winners(&self) -> &[Winner] {
+        &self.winners
     }
 
     fn draw_card(&mut self) -> Card {
-        self.deck.pop().unwrap()
+        self.deck.draw().expect("the deck ran out of cards")
     }
 
     #[inline]
-    fn add_table_card(&mut self) {
+    fn burn_card(&mut self) {
+        let c = self.draw_card();
+        self.burned.push(c);
+    }
+
+    fn add_table_card(&mut self) -> Result<()> {
         let c = self.draw_card();
-        self.community_cards.push(c);
+        self.community_cards.push(c)
     }
 
-    fn advance_phase(&mut self) {
-        match self.phase() {
-            Phase::Preflop => {
-                let _ = self.draw_card(); // burn card
-                for _ in 0..3 {
-                    self.add_table_card();
+    fn advance_phase(&mut self) -> Result<()> {
+        let _deal = debug_span!("deal", phase = %self.phase).entered();
+        match self.phase().next() {
+            Some(next) => {
+                debug!(%next, "dealing the next street");
+                self.burn_card();
+                let dealt = self.community_cards.len();
+                while self.community_cards.len() < next.board_len() {
+                    self.add_table_card()?;
                 }
-                assert_eq!(self.community_cards.len(), 3);
-                self.set_phase(Phase::Flop);
-            }
-            Phase::Flop => {
-                let _ = self.draw_card(); // burn card
-                self.add_table_card();
-                assert_eq!(self.community_cards.len(), 4);
-                self.set_phase(Phase::Turn);
-            }
-            Phase::Turn => {
-                let _ = self.draw_card(); // burn card
-                self.add_table_card();
-                assert_eq!(self.community_cards.len(), 5);
-                self.set_phase(Phase::River);
-                self.showdown();
+                self.events
+                    .push(GameEvent::CardsDealt(self.community_cards[dealt..].into()));
+                self.set_phase(next);
             }
-            Phase::River => unreachable!(),
+            None => self.showdown()?,
         }
+        #[cfg(debug_assertions)]
+        self.assert_deck_integrity();
+        Ok(())
+    }
+
+    /// Check that every card of the deck is somewhere in this game, exactly once
+    ///
+    /// The cards are looked for in the hands of the players, on the table, among the burned
+    /// cards and in the rest of the deck.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a card was dealt twice or went missing.
+    pub fn assert_deck_integrity(&self) {
+        let mut cards = self.deck.cards().clone();
+        cards.extend(self.community_cards.iter());
+        cards.extend(self.burned.iter());
+        for runout in &self.extra_runouts {
+            cards.extend(runout.iter());
+        }
+        for player in &self.players {
+            cards.extend(player.hand());
+        }
+        if let Err(e) = cards.validate_unique() {
+            panic!("deck integrity violated: {e}");
+        }
+        assert_eq!(
+            cards.len(),
+            self.deck_kind.card_count(),
+            "deck integrity violated: cards went missing"
+        );
+    }
+
+    /// Deal the rest of the board without any more betting and settle the hand at showdown
+    ///
+    /// Everyone still in the hand is treated as checking down. Returns the winner of the main
+    /// pot, all winners are available through [`Game::winners`] afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::GameFinished`] if the hand was already settled.
+    pub fn check_down_to_showdown(&mut self) -> Result<Winner> {
+        if self.is_finished() {
+            return Err(PoksError::GameFinished);
+        }
+        while !self.is_finished() {
+            self.advance_phase()?;
+        }
+        self.winner()
+            .ok_or_else(|| err_int!("No winner after the showdown"))
     }
 
     pub fn hand_plus_table(&self, pid: PlayerID) -> CardsDynamic {
@@ -261,123 +650,270 @@ impl Game {
         hand_plus_table
     }
 
-    fn showdown(&mut self) -> Result<()> {
-        let mut evals: Vec<(PlayerID, Eval<FiveCard>, Cards<7>)> = Vec::new();
-        for (pid, player) in self.players.iter().enumerate() {
-            if player.state != PlayerState::Playing {
-                continue;
-            }
-            let mut hand_plus_table: CardsDynamic = player.hand().into();
-            hand_plus_table.extend(self.community_cards.iter());
-            hand_plus_table.sort();
-            // TODO: add better result type and return this as error
-            evals.push((
-                pid,
-                evaluator()
-                    .evaluate_five(&*hand_plus_table)
-                    .expect("could not evaluate"),
-                hand_plus_table
-                    .try_static()
-                    .expect("Hands plus table were not 7 cards"),
-            ));
-        }
-
-        evals.sort_by(|a, b| b.1.cmp(&a.1));
-        if evals[0] == evals[1] {
-            todo!("We have a draw!")
+    /// The best five card hand the player `pid` can make with the cards dealt so far
+    ///
+    /// Returns [`None`] before the flop, and for players that are not in this game.
+    #[must_use]
+    pub fn best_eval(&self, pid: PlayerID) -> Option<Eval<FiveCard>> {
+        if pid >= self.players.len() || self.community_cards.flop().is_none() {
+            return None;
         }
-        let winner = Winner::KnownCards(self.pot(), evals[0].0, evals[0].1, evals[0].2);
-        self.set_winner(winner);
-
-        Ok(())
+        evaluator().evaluate_five(&*self.hand_plus_table(pid)).ok()
     }
 
-    fn next_turn(&mut self) {
-        self.turn = (self.turn + 1) % self.players.len();
-        if self.turn == 0 {
-            self.advance_phase();
-        }
+    /// The class of the [best hand](Game::best_eval) of the player `pid`, like a flush
+    #[must_use]
+    pub fn hand_class(&self, pid: PlayerID) -> Option<FiveCardHandClass> {
+        self.best_eval(pid).map(|eval| eval.classify())
     }
 
-    // BUG: this does not correctly do the betting rounds!
-    pub fn process_action(&mut self, action: Option<Action>) -> Result<()> {
-        let remaining_players = self.players.iter().filter(|p| p.state.is_playing()).count();
-        if remaining_players == 1 {
-            let winner_id = self
-                .players
-                .iter()
-                .enumerate()
-                .find(|(_, p)| p.state.is_playing())
-                .map(|(id, _)| id)
-                .ok_or_else(|| err_int!("No playing players found"))?;
+    fn showdown(&mut self) -> Result<()> {
+        self.events.push(GameEvent::Showdown);
+        self.distribute_pots()
+    }
 
-            self.set_winner(Winner::UnknownCards(self.pot(), winner_id));
-            return Ok(());
+    /// Process the action of the player whose turn it is
+    ///
+    /// Passing [`None`] means that the player has not decided yet. This returns
+    /// [`ActionOutcome::AwaitingAction`] right away and leaves the turn untouched, pacing is up to
+    /// the caller.
+    pub fn process_action(&mut self, action: Option<Action>) -> Result<ActionOutcome> {
+        let _action = debug_span!("action", player = self.turn, phase = %self.phase).entered();
+        if self.settle_uncontested()? {
+            return Ok(ActionOutcome::Finished);
         }
 
-        let round_bet = self.highest_bet_of_round();
-        let player = &current_player!(self);
+        if current_player!(self).state != PlayerState::Playing {
+            // folded or all in, nothing to decide for this player
+            self.next_turn()?;
+            return Ok(ActionOutcome::Skipped);
+        }
 
-        if !player.state.is_playing() {
-            self.next_turn();
+        if current_player!(self).currency() == Currency::ZERO {
+            // a busted seat can not bet anything
+            current_player!(self).state = PlayerState::Folded;
+            glog!(self, self.turn, "has no chips left and folds".to_string());
+            self.events.push(GameEvent::Acted(self.turn, Action::Fold));
+            self.next_turn()?;
+            self.settle_uncontested()?;
+            return Ok(ActionOutcome::Skipped);
         }
 
         let action = match action {
             Some(a) => a,
-            None => return Ok(()), // come back with an action
+            None => return Ok(ActionOutcome::AwaitingAction), // come back with an action
+        };
+
+        self.apply_action(action)?;
+        debug!(%action, "action applied");
+        self.events.push(GameEvent::Acted(self.turn, action));
+
+        if self.verbose_log {
+            let player = &current_player!(self);
+            let (round_bet, stack) = (player.round_bet, player.currency());
+            glogf!(
+                self,
+                self.turn,
+                "{action} (round bet {round_bet}, stack {stack})"
+            );
+        } else {
+            glogf!(self, self.turn, "{action}");
+        }
+
+        self.next_turn()?;
+        self.settle_uncontested()?;
+
+        if self.is_finished() {
+            Ok(ActionOutcome::Finished)
+        } else {
+            Ok(ActionOutcome::Applied)
+        }
+    }
+
+    /// Act for the player whose turn it is, because they did not decide in time
+    ///
+    /// The player checks if nothing is owed, otherwise they fold. The timeout is written to the
+    /// game log before the action.
+    pub fn act_on_timeout(&mut self) -> Result<ActionOutcome> {
+        let action = match self.action_call() {
+            Action::Check => Action::Check,
+            _ => Action::Fold,
         };
+        glogf!(self, self.turn, "{}", PoksError::PlayerTimeout);
+        self.process_action(Some(action))
+    }
 
-        if !current_player!(self).state.is_playing() {
-            return Ok(()); // ignore
-            // return Err(PoksError::player_not_playing(
-            //     self.turn,
-            //     format!("{:?}", current_player!(self).state),
-            // ));
+    /// If only one player is left in the hand, they win the pot without a showdown
+    ///
+    /// Returns whether the hand is finished.
+    fn settle_uncontested(&mut self) -> Result<bool> {
+        if self.is_finished() {
+            return Ok(true);
+        }
+        let remaining_players = self.players.iter().filter(|p| p.state.is_playing()).count();
+        if remaining_players != 1 {
+            return Ok(false);
         }
+        let winner_id = self
+            .players
+            .iter()
+            .enumerate()
+            .find(|(_, p)| p.state.is_playing())
+            .map(|(id, _)| id)
+            .ok_or_else(|| err_int!("No playing players found"))?;
+
+        let pot = self.take_rake(self.pot());
+        self.set_winner(Winner::UnknownCards(pot, winner_id))?;
+        Ok(true)
+    }
 
-        if current_player!(self).state == PlayerState::AllIn {
-            self.next_turn();
-            return Ok(());
+    /// Check whether the player `pid` could take `action` right now, without taking it
+    ///
+    /// This runs the same checks as [`Game::process_action`] does before it changes anything.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error that processing the action would fail with, for example
+    /// [`PoksError::NotPlayersTurn`], [`PoksError::TooLowBetAmount`] or
+    /// [`PoksError::RaiseNotAllowed`].
+    pub fn validate_action(&self, pid: PlayerID, action: Action) -> Result<()> {
+        if self.is_finished() {
+            return Err(PoksError::GameFinished);
         }
+        let player = self
+            .players
+            .get(pid)
+            .ok_or_else(|| PoksError::invalid_player(pid, self.players.len() - 1))?;
+        if pid != self.turn {
+            return Err(PoksError::NotPlayersTurn {
+                player_id: pid,
+                turn: self.turn,
+            });
+        }
+        if player.state == PlayerState::AllIn && matches!(action, Action::AllIn(_)) {
+            return Err(PoksError::PlayerAlreadyAllIn { player_id: pid });
+        }
+        if player.state != PlayerState::Playing {
+            return Err(PoksError::player_not_playing(pid, player.state));
+        }
+
+        let round_bet = self.highest_bet_of_round();
         match action {
-            Action::Fold => {
-                current_player!(self).state = PlayerState::Folded;
+            Action::Fold => (),
+            Action::Check => {
+                if round_bet > player.round_bet {
+                    return Err(PoksError::InvalidCheck {
+                        to_call: round_bet - player.round_bet,
+                    });
+                }
             }
             Action::Call(currency) => {
-                if round_bet < current_player!(self).round_bet {
+                // there is nothing to call, that would be a check
+                if round_bet <= player.round_bet {
                     return Err(PoksError::InvalidCall);
                 }
-                let diff = round_bet - current_player!(self).round_bet;
+                let diff = round_bet - player.round_bet;
                 if diff != currency {
                     return Err(PoksError::call_mismatch(diff, currency));
                 }
-                if currency != CU!(0) {
-                    current_player!(self).round_bet += currency;
+                let available = player.currency();
+                if diff > available {
+                    return Err(PoksError::insufficient_funds(diff, available));
                 }
             }
             Action::Raise(currency) => {
                 if self.state == GameState::RaiseDisallowed {
                     return Err(PoksError::RaiseNotAllowed);
                 }
-                current_player!(self).round_bet += currency;
+                let available = player.currency();
+                if currency > available {
+                    return Err(PoksError::insufficient_funds(currency, available));
+                }
+                let increment = (player.round_bet + currency).saturating_sub(round_bet);
+                if increment < self.min_raise {
+                    return Err(PoksError::TooLowBetAmount {
+                        amount: increment,
+                        minimum: self.min_raise,
+                    });
+                }
+                self.check_raise_limit(pid, increment)?;
             }
             Action::AllIn(currency) => {
-                if current_player!(self).state == PlayerState::AllIn {
-                    return Err(PoksError::PlayerAlreadyAllIn {
-                        player_id: self.turn,
+                if currency == Currency::ZERO {
+                    return Err(PoksError::CannotActWithZeroStack { player_id: pid });
+                }
+                let stack = player.currency();
+                if currency != stack {
+                    return Err(PoksError::AllInAmountMismatch {
+                        stack,
+                        actual: currency,
                     });
                 }
-                if self.state != GameState::RaiseDisallowed {
-                    todo!("No betting allowed, just calling")
+                if self.state == GameState::RaiseDisallowed
+                    && player.round_bet + currency > round_bet
+                {
+                    return Err(PoksError::RaiseNotAllowed);
                 }
-                current_player!(self).state = PlayerState::AllIn;
-                current_player!(self).round_bet += currency;
+                self.check_raise_limit(
+                    pid,
+                    (player.round_bet + currency).saturating_sub(round_bet),
+                )?;
             }
         }
+        Ok(())
+    }
+
+    /// Make sure raising by `increment` stays within the [`BettingLimit`] of this game
+    fn check_raise_limit(&self, pid: PlayerID, increment: Currency) -> Result<()> {
+        match self.max_raise_for(pid) {
+            Some(maximum) if increment > maximum => Err(PoksError::TooHighBetAmount {
+                amount: increment,
+                maximum,
+            }),
+            _ => Ok(()),
+        }
+    }
 
-        glogf!(self, self.turn, "{action}");
+    /// Apply the action of the player whose turn it is to the bets of this round
+    ///
+    /// The chips of a bet are taken from the seat of the player. A player whose stack is empty
+    /// afterwards is all in, no matter which action got them there.
+    fn apply_action(&mut self, action: Action) -> Result<()> {
+        self.validate_action(self.turn, action)?;
+        let round_bet = self.highest_bet_of_round();
+        let chips = action.chips_required(self, self.turn);
+        let player = &mut current_player!(self);
+        player.seat.withdraw_currency(chips)?;
+        player.round_bet += chips;
+        if action == Action::Fold {
+            player.state = PlayerState::Folded;
+        } else if player.currency() == Currency::ZERO {
+            player.state = PlayerState::AllIn;
+        }
 
-        self.next_turn();
+        if current_player!(self).round_bet > round_bet {
+            let increment = current_player!(self).round_bet - round_bet;
+            let full_raise = increment >= self.min_raise;
+            // the bet was raised, everyone gets to respond
+            for player in self.players.iter_mut() {
+                if full_raise {
+                    player.can_raise = true;
+                } else if player.acted {
+                    // an all in below a full raise does not reopen the betting for those who
+                    // already acted
+                    player.can_raise = false;
+                }
+                player.acted = false;
+            }
+            if full_raise {
+                self.min_raise = increment;
+                self.bets_this_street += 1;
+            }
+            self.last_aggressor = Some(self.turn);
+        }
+        current_player!(self).acted = true;
+        self.last_action = Some((self.turn, action));
+        self.action_history.push((self.turn, action));
 
         Ok(())
     }
@@ -385,7 +921,7 @@ impl Game {
     pub fn show_table(&self) -> String {
         let mut buf = String::new();
 
-        for i in 0..5 {
+        for i in 0..Board::MAX_CARDS {
             let card: String = self
                 .community_cards
                 .get(i)
@@ -397,6 +933,59 @@ impl Game {
         buf
     }
 
+    /// Render the table as plain text, for frontends without a terminal UI
+    ///
+    /// The first lines hold the pot, the street and the board, followed by one line per seat
+    /// with its stack, its bet in this round, its hole cards and its state. The seat whose turn
+    /// it is is marked with `>`, the dealer and the blinds with `D`, `SB` and `BB`. Only the
+    /// hole cards of `viewer` and those [revealed at the showdown](Game::should_reveal) are
+    /// shown, a spectator without a seat passes [`None`] to see all of them.
+    #[must_use]
+    pub fn to_ascii_table(&self, viewer: Option<PlayerID>) -> String {
+        let plain = |amount: Currency| amount.format(FormatOpts::PLAIN);
+        let board: Vec<String> = (0..Board::MAX_CARDS)
+            .map(|i| match self.community_cards.get(i) {
+                Some(card) => show_cards_ascii(&[*card]),
+                None => "--".to_string(),
+            })
+            .collect();
+        let mut buf = format!("Pot: {} | {}\n", plain(self.pot()), self.phase.as_str());
+        buf.push_str(&format!("Board: {}\n", board.join(" ")));
+
+        let blinds = (self.small_blind_position(), self.big_blind_position());
+        for (pid, player) in self.players.iter().enumerate() {
+            let turn = if pid == self.turn && !self.is_finished() {
+                '>'
+            } else {
+                ' '
+            };
+            let markers: Vec<&str> = [(self.dealer, "D"), (blinds.0, "SB"), (blinds.1, "BB")]
+                .into_iter()
+                .filter(|(position, _)| *position == pid)
+                .map(|(_, marker)| marker)
+                .collect();
+            let name = match player.seat.name() {
+                Some(name) => name.to_string(),
+                None => format!("Seat {pid}"),
+            };
+            let cards = if player.state == PlayerState::Lost {
+                "     ".to_string()
+            } else if viewer.is_none_or(|viewer| viewer == pid) || self.should_reveal(pid) {
+                show_cards_ascii(&player.hand())
+            } else {
+                "?? ??".to_string()
+            };
+            buf.push_str(&format!(
+                "{turn}{pid:>2} {:<5} {name:<12} {:>10} {:>10}  {cards}  {}\n",
+                markers.join("/"),
+                plain(player.currency()),
+                plain(player.round_bet),
+                player.state
+            ));
+        }
+        buf
+    }
+
     pub fn turn(&self) -> PlayerID {
         self.turn
     }
@@ -405,11 +994,18 @@ impl Game {
         &self.players
     }
 
-    pub fn community_cards(&self) -> &CardsDynamic {
+    pub fn community_cards(&self) -> &Board {
         &self.community_cards
     }
 
-    pub fn deck(&self) -> &CardsDynamic {
+    /// The community cards, grouped by the street they were dealt on
+    #[must_use]
+    pub fn board_by_street(&self) -> BoardByStreet {
+        self.community_cards.by_street()
+    }
+
+    /// The cards that were not dealt yet
+    pub fn deck(&self) -> &Deck {
         &self.deck
     }
 
@@ -417,46 +1013,262 @@ impl Game {
         self.state
     }
 
+    /// The passive action of the player whose turn it is
+    ///
+    /// This is a [`Action::Check`] if there is nothing to call, and a [`Action::Call`] of the
+    /// difference to the highest bet of the round otherwise.
     pub fn action_call(&self) -> Action {
         let diff = self.highest_bet_of_round() - self.players[self.turn].round_bet;
-        Action::Call(diff)
+        if diff == Currency::ZERO {
+            Action::Check
+        } else {
+            Action::Call(diff)
+        }
+    }
+
+    /// Whether only two players are left at the table for this hand
+    ///
+    /// Once the others were eliminated, the heads-up rules for the button and the blinds apply.
+    /// Players who folded in this hand still count, only eliminated players are left out.
+    #[must_use]
+    pub fn heads_up_after_eliminations(&self) -> bool {
+        self.players
+            .iter()
+            .filter(|p| p.state != PlayerState::Lost)
+            .count()
+            == 2
+    }
+
+    /// The next player after `pid` who was dealt into this hand
+    fn next_dealt_in(&self, pid: PlayerID) -> PlayerID {
+        let n = self.players.len();
+        (1..=n)
+            .map(|offset| (pid + offset) % n)
+            .find(|p| self.players[*p].state != PlayerState::Lost)
+            .unwrap_or(pid)
     }
 
     pub fn small_blind_position(&self) -> PlayerID {
-        if self.players.len() == 2 {
+        if self.heads_up_after_eliminations()
+            && self.players[self.dealer].state != PlayerState::Lost
+        {
             // In heads-up, dealer posts small blind
             self.dealer
         } else {
-            (self.dealer + 1) % self.players.len()
+            self.next_dealt_in(self.dealer)
         }
     }
 
     pub fn big_blind_position(&self) -> PlayerID {
-        if self.players.len() == 2 {
-            // In heads-up, non-dealer posts big blind
-            (self.dealer + 1) % self.players.len()
-        } else {
-            (self.dealer + 2) % self.players.len()
-        }
+        // In heads-up, this is the non-dealer
+        self.next_dealt_in(self.small_blind_position())
     }
 
     fn post_blinds(&mut self) -> Result<()> {
         let sb_pos = self.small_blind_position();
         let bb_pos = self.big_blind_position();
 
-        let sbp = &mut self.players[sb_pos];
-        *sbp.seat.behavior_mut().currency_mut() -= self.small_blind;
-        sbp.round_bet += self.small_blind;
-        glogf!(self, sb_pos, "Posts the small blind ({})", self.small_blind);
-
-        let bbp = &mut self.players[bb_pos];
-        *bbp.seat.behavior_mut().currency_mut() -= self.small_blind;
-        self.players[bb_pos].round_bet += self.big_blind;
-        glogf!(self, bb_pos, "Posts the big blind ({})", self.big_blind);
+        let posted = self.post_blind(sb_pos, self.small_blind)?;
+        glogf!(self, sb_pos, "Posts the small blind ({posted})");
+        let posted = self.post_blind(bb_pos, self.big_blind)?;
+        glogf!(self, bb_pos, "Posts the big blind ({posted})");
 
         Ok(())
     }
 
+    /// Let the player `pid` post a blind, or all they have if that is less
+    ///
+    /// Returns what was posted. A player who posts all they have is all in.
+    fn post_blind(&mut self, pid: PlayerID, blind: Currency) -> Result<Currency> {
+        let player = &mut self.players[pid];
+        let posted = blind.min(player.currency());
+        player.seat.withdraw_currency(posted)?;
+        player.round_bet += posted;
+        if player.currency() == Currency::ZERO {
+            player.state = PlayerState::AllIn;
+        }
+        self.events.push(GameEvent::BlindPosted(pid, posted));
+        Ok(posted)
+    }
+
+    /// Let the player `pid` pay the big blind they missed while they were out of the game
+    ///
+    /// The blind is dead money: it goes into the pot, but does not count towards the bet of the
+    /// player in this round. Returns what was posted, a player who posts all they have is all in.
+    pub(crate) fn post_missed_blind(&mut self, pid: PlayerID) -> Result<Currency> {
+        let blind = self.big_blind;
+        let player = &mut self.players[pid];
+        let posted = blind.min(player.currency());
+        player.seat.withdraw_currency(posted)?;
+        player.total_bet += posted;
+        if player.currency() == Currency::ZERO {
+            player.state = PlayerState::AllIn;
+        }
+        self.dead_blinds.push(pid);
+        glogf!(self, pid, "Posts a missed big blind ({posted})");
+        self.events.push(GameEvent::BlindPosted(pid, posted));
+        Ok(posted)
+    }
+
+    /// The smallest amount a bet has to be raised by
+    ///
+    /// This is the size of the last full raise of the current street, or the big blind if there
+    /// was none.
+    #[must_use]
+    pub fn min_raise_amount(&self) -> Currency {
+        self.min_raise
+    }
+
+    /// The actions the player whose turn it is can legally take
+    ///
+    /// See [`Game::legal_actions`].
+    #[must_use]
+    pub fn valid_actions(&self) -> Vec<Action> {
+        self.legal_actions(self.turn)
+    }
+
+    /// The actions the player `pid` could legally take if it was their turn
+    ///
+    /// Raises are listed with the smallest legal amount. Folding is always possible, checking
+    /// only if there is nothing to call, and calling only if the stack covers it, otherwise the
+    /// player can just go all in. Going all in for more than the [`BettingLimit`] allows is not
+    /// listed.
+    #[must_use]
+    pub fn legal_actions(&self, pid: PlayerID) -> Vec<Action> {
+        let player = &self.players[pid];
+        if player.state != PlayerState::Playing {
+            return Vec::new();
+        }
+        let stack = player.currency();
+        let to_call = self.highest_bet_of_round().saturating_sub(player.round_bet);
+        let mut actions = vec![Action::Fold];
+        if to_call == Currency::ZERO {
+            actions.push(Action::Check);
+        } else if to_call < stack {
+            actions.push(Action::Call(to_call));
+        }
+        let can_raise = player.can_raise && !self.raise_cap_reached();
+        let min_raise = to_call + self.min_raise;
+        if can_raise && min_raise < stack {
+            actions.push(Action::Raise(min_raise));
+        }
+        let all_in_allowed = self
+            .max_raise_for(pid)
+            .is_none_or(|max| stack.saturating_sub(to_call) <= max);
+        if stack > Currency::ZERO && ((can_raise && all_in_allowed) || stack <= to_call) {
+            actions.push(Action::AllIn(stack));
+        }
+        actions
+    }
+
+    /// Whether the player `pid` has the chips to take `action`
+    ///
+    /// Unlike [`Game::validate_action`], this only looks at the stack of the player, not at whose
+    /// turn it is or whether raising is allowed. A call needs the difference to the highest bet
+    /// of the round, and a raise has to cover that call portion before it raises anything. Going
+    /// all in needs any chips at all. Players that are not at the table can not afford anything.
+    #[must_use]
+    pub fn player_can_afford(&self, pid: PlayerID, action: Action) -> bool {
+        let Some(player) = self.players.get(pid) else {
+            return false;
+        };
+        let stack = player.currency();
+        let to_call = self.highest_bet_of_round().saturating_sub(player.round_bet);
+        match action {
+            Action::Fold | Action::Check => true,
+            Action::Call(_) => to_call <= stack,
+            Action::Raise(amount) => amount > to_call && amount <= stack,
+            Action::AllIn(_) => stack > Currency::ZERO,
+        }
+    }
+
+    /// The player who made the last bet or raise on the current street
+    #[must_use]
+    pub fn last_aggressor(&self) -> Option<PlayerID> {
+        self.last_aggressor
+    }
+
+    /// The last action that was applied in this hand and who made it
+    #[must_use]
+    pub fn last_action(&self) -> Option<(PlayerID, Action)> {
+        self.last_action
+    }
+
+    /// All actions applied in this hand so far, in order
+    #[must_use]
+    pub fn action_history(&self) -> &[(PlayerID, Action)] {
+        &self.action_history
+    }
+
+    /// The aggression factor of a player in this hand
+    ///
+    /// This is the number of raises and all ins divided by the number of calls. Checks do not
+    /// count. Returns [`f64::INFINITY`] for a player that was aggressive but never called.
+    #[must_use]
+    pub fn player_aggression(&self, pid: PlayerID) -> f64 {
+        let (mut aggressive, mut passive) = (0u32, 0u32);
+        for (_, action) in self.action_history.iter().filter(|(p, _)| *p == pid) {
+            match action {
+                Action::Raise(_) | Action::AllIn(_) => aggressive += 1,
+                Action::Call(_) => passive += 1,
+                _ => (),
+            }
+        }
+        match (aggressive, passive) {
+            (0, _) => 0.0,
+            (_, 0) => f64::INFINITY,
+            (a, p) => a as f64 / p as f64,
+        }
+    }
+
+    /// The fraction of the actions of a player in this hand that were folds
+    #[must_use]
+    pub fn fold_percentage(&self, pid: PlayerID) -> f64 {
+        let actions = self.action_history.iter().filter(|(p, _)| *p == pid);
+        let (total, folds) = actions.fold((0u32, 0u32), |(total, folds), (_, action)| {
+            (total + 1, folds + u32::from(*action == Action::Fold))
+        });
+        if total == 0 {
+            0.0
+        } else {
+            folds as f64 / total as f64
+        }
+    }
+
+    /// The share of the pot after calling that the player `pid` has to put in to call
+    ///
+    /// This is the call amount divided by the pot plus the call amount, `0.0` if there is
+    /// nothing to call. A player who can not cover the call only pays their stack.
+    #[must_use]
+    pub fn pot_odds(&self, pid: PlayerID) -> f64 {
+        let player = &self.players[pid];
+        let call = self
+            .highest_bet_of_round()
+            .saturating_sub(player.round_bet)
+            .min(player.currency());
+        if call == Currency::ZERO {
+            return 0.0;
+        }
+        *call as f64 / *(self.pot() + call) as f64
+    }
+
+    /// How much the player `pid` can win or lose from their stack at most in this hand
+    ///
+    /// This is the smaller of their stack and the largest stack of the other players still in
+    /// the hand.
+    #[must_use]
+    pub fn effective_stack(&self, pid: PlayerID) -> Currency {
+        let largest_other = self
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(other, p)| *other != pid && p.state.is_playing())
+            .map(|(_, p)| p.currency())
+            .max()
+            .unwrap_or(Currency::ZERO);
+        self.players[pid].currency().min(largest_other)
+    }
+
     pub fn gamelog(&self) -> &[GlogItem] {
         &self.game_log
     }
@@ -467,6 +1279,53 @@ impl Game {
         a
     }
 
+    /// Everything that happened in this game so far, in order
+    #[must_use]
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
+    /// Take the events of this game, leaving no events behind
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Whether the log entries of actions include the resulting round bet and stack
+    #[must_use]
+    pub fn verbose_log(&self) -> bool {
+        self.verbose_log
+    }
+
+    pub fn set_verbose_log(&mut self, verbose: bool) {
+        self.verbose_log = verbose;
+    }
+
+    /// Take a commission from the pots of this game, see [`RakeConfig`]
+    pub fn set_rake(&mut self, rake: Option<RakeConfig>) {
+        self.rake = rake;
+    }
+
+    /// What was raked from the pots of this game so far
+    #[must_use]
+    pub fn rake(&self) -> Currency {
+        self.rake_taken
+    }
+
+    /// Take the rake from a pot that is about to be awarded, returning what is left for the
+    /// winners
+    fn take_rake(&mut self, pot: Currency) -> Currency {
+        let Some(config) = self.rake else {
+            return pot;
+        };
+        if self.community_cards.is_empty() {
+            // no flop, no drop
+            return pot;
+        }
+        let rake = config.rake_for(pot, self.rake_taken);
+        self.rake_taken += rake;
+        pot - rake
+    }
+
     pub fn big_blind(&self) -> Currency {
         self.big_blind
     }
@@ -478,6 +1337,104 @@ impl Game {
     pub fn dealer_position(&self) -> PlayerID {
         self.dealer
     }
+
+    /// Whether the given player is all in
+    #[must_use]
+    pub fn is_all_in(&self, pid: PlayerID) -> bool {
+        self.players[pid].state == PlayerState::AllIn
+    }
+
+    /// How many players are all in
+    #[must_use]
+    pub fn all_in_count(&self) -> usize {
+        self.players
+            .iter()
+            .filter(|p| p.state == PlayerState::AllIn)
+            .count()
+    }
+
+    /// Estimate the pot equity of every player that is still in the hand
+    ///
+    /// The missing board cards are sampled [`ALL_IN_EQUITY_ITERATIONS`] times with a random
+    /// generator derived from the seed of this game, so the result is stable for a given game.
+    /// This is only available once betting is closed, that is when at most one of the remaining
+    /// players is not all in.
+    pub fn all_in_equities(&self) -> Result<Vec<(PlayerID, f64)>> {
+        if !self.is_betting_closed() {
+            return Err(PoksError::BettingNotClosed);
+        }
+        let pids: Vec<PlayerID> = self
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.state.is_playing())
+            .map(|(pid, _)| pid)
+            .collect();
+        let hands: Vec<Cards<2>> = pids.iter().map(|pid| self.players[*pid].hand()).collect();
+        let mut rng = RNG::from_seed(self.seed);
+        let equities = equity::hand_equities(
+            &hands,
+            &self.community_cards,
+            self.deck_kind,
+            ALL_IN_EQUITY_ITERATIONS,
+            &mut rng,
+        )?;
+        Ok(pids.into_iter().zip(equities).collect())
+    }
+
+    /// No more bets can be made in this hand, the board only needs to be run out
+    fn is_betting_closed(&self) -> bool {
+        let in_hand = self.players.iter().filter(|p| p.state.is_playing()).count();
+        let highest = self.highest_bet_of_round();
+        let mut can_act = self
+            .players
+            .iter()
+            .filter(|p| p.state == PlayerState::Playing);
+        in_hand >= 2
+            && match (can_act.next(), can_act.next()) {
+                (None, _) => true,
+                (Some(player), None) => player.round_bet >= highest,
+                _ => false,
+            }
+    }
+
+    /// How many players have acted since the current street began or the bet was last raised
+    #[must_use]
+    pub fn acted_this_street(&self) -> usize {
+        self.players.iter().filter(|p| p.acted).count()
+    }
+
+    /// How many players still need to act before the current street can be completed
+    ///
+    /// Only players who are still in the hand and have chips left are counted. A player needs
+    /// to act if they did not act yet or if they are not matching the current bet.
+    #[must_use]
+    pub fn remaining_to_act(&self) -> usize {
+        let highest = self.highest_bet_of_round();
+        self.players
+            .iter()
+            .filter(|p| p.state == PlayerState::Playing && p.currency() > Currency::ZERO)
+            .filter(|p| !p.acted || p.round_bet < highest)
+            .count()
+    }
+
+    /// Express an amount in big blinds of this game
+    #[must_use]
+    pub fn bb_count(&self, amount: Currency) -> f64 {
+        amount.in_blinds(self.big_blind)
+    }
+
+    /// The stack of a player in big blinds
+    #[must_use]
+    pub fn stack_in_bb(&self, pid: PlayerID) -> f64 {
+        self.bb_count(self.players[pid].currency())
+    }
+
+    /// The pot in big blinds
+    #[must_use]
+    pub fn pot_in_bb(&self) -> f64 {
+        self.bb_count(self.pot())
+    }
 }
 
 impl Player {
@@ -492,6 +1449,8 @@ impl Player {
             state: Default::default(),
             total_bet: Default::default(),
             round_bet: Default::default(),
+            acted: false,
+            can_raise: true,
             seat: lobby_seat,
         };
         p.set_hand(hand);
@@ -532,6 +1491,41 @@ impl Player {
     }
 }
 
+impl DeckKind {
+    /// How many cards a full deck of this kind has
+    pub const fn card_count(&self) -> usize {
+        match self {
+            DeckKind::Standard => 52,
+            DeckKind::ShortDeck => 36,
+        }
+    }
+
+    /// All cards of this deck, unshuffled and in the order of [`full_deck`]
+    pub fn cards(&self) -> CardsDynamic {
+        match self {
+            DeckKind::Standard => full_deck(),
+            DeckKind::ShortDeck => full_deck()
+                .iter()
+                .filter(|c| !matches!(c.rank(), Rank::Two | Rank::Three | Rank::Four | Rank::Five))
+                .copied()
+                .collect::<Vec<_>>()
+                .into(),
+        }
+    }
+
+    /// All cards of this deck, in random order
+    pub fn shuffled(&self, rng: &mut RNG) -> CardsDynamic {
+        match self {
+            DeckKind::Standard => poker::deck::shuffled_with(rng).into(),
+            DeckKind::ShortDeck => {
+                let mut deck = self.cards();
+                deck.shuffle(rng);
+                deck
+            }
+        }
+    }
+}
+
 impl GameState {
     #[inline]
     #[must_use]
@@ -546,27 +1540,165 @@ impl GameState {
 impl Action {
     #[inline]
     pub fn check() -> Self {
-        Self::Call(CU!(0))
+        Self::Check
+    }
+
+    /// Parse a text command into the action of the player whose turn it is in `game`
+    ///
+    /// The commands are `fold`, `check`, `call`, `raise <amount>` and `allin`, in any case. The
+    /// amount of a call is the difference to the highest bet of the round, calling when there
+    /// is nothing to call is a check. `raise 20` raises the highest bet of the round by 20, the
+    /// call portion is added to the [`Action::Raise`]. Going all in puts in the whole stack.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::InvalidCommand`] for unknown commands or a missing amount,
+    /// [`PoksError::CurrencyParseError`] for amounts that are not a [`Currency`], and whatever
+    /// [`Game::validate_action`] returns if the player can not take the action, for example
+    /// [`PoksError::TooLowBetAmount`].
+    pub fn from_command(s: &str, game: &Game) -> Result<Self> {
+        let err = |reason: &str| PoksError::InvalidCommand {
+            input: s.to_string(),
+            reason: reason.to_string(),
+        };
+        let command = s.trim().to_lowercase();
+        let mut words = command.split_whitespace();
+        let verb = words.next().ok_or_else(|| err("the command is empty"))?;
+        let amount = words.next();
+        if words.next().is_some() {
+            return Err(err("too many arguments"));
+        }
+        if amount.is_some() && verb != "raise" {
+            return Err(err("only a raise takes an amount"));
+        }
+
+        let pid = game.turn();
+        let action = match verb {
+            "fold" => Action::Fold,
+            "check" => Action::Check,
+            "call" => game.action_call(),
+            "raise" => {
+                let amount: Currency = amount
+                    .ok_or_else(|| err("a raise needs an amount"))?
+                    .parse()?;
+                let to_call = game.highest_bet_of_round() - game.players[pid].round_bet;
+                Action::Raise(to_call + amount)
+            }
+            "allin" | "all-in" => Action::AllIn(game.players[pid].currency()),
+            _ => return Err(err("unknown command")),
+        };
+        game.validate_action(pid, action)?;
+        Ok(action)
+    }
+
+    /// How aggressive this action is, from 0 for a fold up to 4 for going all in
+    ///
+    /// The amount does not matter, every raise is more aggressive than any call.
+    #[must_use]
+    pub const fn aggression_rank(&self) -> u8 {
+        match self {
+            Action::Fold => 0,
+            Action::Check => 1,
+            Action::Call(_) => 2,
+            Action::Raise(_) => 3,
+            Action::AllIn(_) => 4,
+        }
+    }
+
+    /// The amount written in this action, zero for folding and checking
+    ///
+    /// This does not look at the game, see [`Action::chips_required`] for what actually leaves
+    /// the stack.
+    #[must_use]
+    pub fn committed_amount(&self) -> Currency {
+        match self {
+            Action::Fold | Action::Check => Currency::ZERO,
+            Action::Call(amount) | Action::Raise(amount) | Action::AllIn(amount) => *amount,
+        }
+    }
+
+    /// How many chips leave the stack of the player `pid` if they take this action in `game`
+    ///
+    /// Folding and checking are free, a call costs the difference to the highest bet of the
+    /// round, a raise costs the amount raised by and going all in costs the whole stack.
+    #[must_use]
+    pub fn chips_required(&self, game: &Game, pid: PlayerID) -> Currency {
+        match self {
+            Action::Fold | Action::Check => Currency::ZERO,
+            Action::Call(_) => game.highest_bet_of_round() - game.players[pid].round_bet,
+            Action::Raise(amount) => *amount,
+            Action::AllIn(_) => game.players[pid].currency(),
+        }
     }
 }
 
 impl Winner {
+    /// Add the winnings to the seats of the winning players
+    ///
+    /// The rake was already taken when the winner was determined, the amounts of a winner are
+    /// what is left of the pot after it, see [`Game::set_rake`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::TransactionError`] if nothing was won, and
+    /// [`PoksError::InvalidPlayerId`] if a winner is not a player of `game`. Nobody is paid
+    /// then. Returns [`PoksError::CurrencyOverflow`] if the currency of a seat would overflow,
+    /// that seat keeps its old currency then.
     pub fn payout(&self, game: &Game) -> Result<()> {
         info!("Payout!");
-        let player = &game.players[self.pid()];
-        let old = player.currency();
-        let winnings = game.pot();
-        assert_ne!(winnings, CU!(0));
-        *player.seat.behavior_mut().currency_mut() += game.pot();
-        assert_eq!(old + winnings, player.currency());
-        debug!("After Payout? {}", player.currency());
+        if self.amount() == Currency::ZERO {
+            return Err(PoksError::TransactionError {
+                reason: format!("player {} won an empty pot", self.pid()),
+            });
+        }
+        let shares = self.shares();
+        if let Some((pid, _)) = shares.iter().find(|(pid, _)| *pid >= game.players.len()) {
+            return Err(PoksError::invalid_player(*pid, game.players.len() - 1));
+        }
+        for (pid, winnings) in shares {
+            let player = &game.players[pid];
+            player.seat.add_currency(winnings)?;
+            debug!("After Payout? {}", player.currency());
+        }
         Ok(())
     }
 
+    /// Who won how much
+    pub fn shares(&self) -> Vec<(PlayerID, Currency)> {
+        match self {
+            Winner::UnknownCards(amount, pid) => vec![(*pid, *amount)],
+            Winner::KnownCards(amount, pid, ..) => vec![(*pid, *amount)],
+            Winner::Split(shares, _) => shares
+                .iter()
+                .map(|(pid, amount, _)| (*pid, *amount))
+                .collect(),
+        }
+    }
+
+    /// What this winner won, all shares together if the pot was split
+    pub fn amount(&self) -> Currency {
+        match self {
+            Winner::UnknownCards(amount, _) => *amount,
+            Winner::KnownCards(amount, ..) => *amount,
+            Winner::Split(shares, _) => shares.iter().map(|(_, amount, _)| *amount).sum(),
+        }
+    }
+
+    /// Order winners by the [`amount`](Winner::amount) they won, the smallest first
+    ///
+    /// Winners of equal amounts are ordered by their [`pid`](Winner::pid).
+    pub fn cmp_by_amount(&self, other: &Self) -> std::cmp::Ordering {
+        self.amount()
+            .cmp(&other.amount())
+            .then_with(|| self.pid().cmp(&other.pid()))
+    }
+
+    /// The winning player, or the first one who got a share of a split pot
     pub fn pid(&self) -> PlayerID {
         match self {
             Winner::UnknownCards(_, pid) => *pid,
             Winner::KnownCards(_, pid, ..) => *pid,
+            Winner::Split(shares, _) => shares[0].0,
         }
     }
 }
@@ -579,11 +1711,97 @@ pub fn show_cards(cards: &[impl Display]) -> String {
     buf
 }
 
+/// Show cards in plain ASCII like `Th Td`, for logs and terminals without the suit glyphs
+pub fn show_cards_ascii(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(|card| format!("{}{}", card.rank().as_char(), card.suit().as_char()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 #[inline]
 pub fn evaluator() -> &'static Evaluator {
     EVALUATOR.get_or_init(Evaluator::new)
 }
 
+/// Evaluate seven cards without copying them anywhere
+///
+/// The cards do not need to be sorted. This is the same as
+/// [`Evaluator::evaluate_five`] with the shared [`evaluator`], meant for hot loops that already
+/// hold the cards of a hand plus the board in an array.
+///
+/// # Panics
+///
+/// Panics if a card is contained more than once.
+#[must_use]
+pub fn eval7(cards: &Cards<7>) -> Eval<FiveCard> {
+    evaluator()
+        .evaluate_five(cards)
+        .expect("seven distinct cards can always be evaluated")
+}
+
+/// The index of the best of the given seven card hands
+///
+/// If several hands are equally strong, the first of them is returned.
+///
+/// # Panics
+///
+/// Panics if `hands` is empty, or a hand contains a card more than once.
+#[must_use]
+pub fn best_of(hands: &[Cards<7>]) -> usize {
+    let mut best = (0, eval7(&hands[0]));
+    for (idx, hand) in hands.iter().enumerate().skip(1) {
+        let eval = eval7(hand);
+        if eval > best.1 {
+            best = (idx, eval);
+        }
+    }
+    best.0
+}
+
+/// All 52 cards of a standard deck, unshuffled
+pub fn full_deck() -> CardsDynamic {
+    const RANKS: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+    const SUITS: [Suit; 4] = [Suit::Clubs, Suit::Hearts, Suit::Spades, Suit::Diamonds];
+    let mut deck = CardsDynamic::new();
+    for suit in SUITS {
+        for rank in RANKS {
+            deck.push(Card::new(rank, suit));
+        }
+    }
+    deck
+}
+
+/// The cards of a deck of `deck_kind` that are not `known`, in the order of [`full_deck`]
+///
+/// `known` are the cards that were seen, like the own hand, the board and hands that were
+/// shown. Sampling from the rest keeps simulations from dealing them a second time.
+///
+/// # Errors
+///
+/// Returns [`PoksError::DuplicateCard`] if a card is known more than once.
+pub fn remaining_deck(deck_kind: DeckKind, known: &[Card]) -> Result<CardsDynamic> {
+    CardsDynamic::from(known.to_vec()).validate_unique()?;
+    let mut deck = deck_kind.cards();
+    deck.retain(|card| !known.contains(card));
+    Ok(deck)
+}
+
 pub fn show_eval_cards(cls: FiveCardHandClass, cards: &Cards<7>) -> String {
     assert!(cards.is_sorted());
 
@@ -691,15 +1909,969 @@ pub fn show_eval_cards(cls: FiveCardHandClass, cards: &Cards<7>) -> String {
     show_cards(&cards)
 }
 
+/// Seats of `n` computer players with `stack` each, for tests
+#[cfg(test)]
+pub(crate) fn test_seats(n: usize, stack: Currency) -> Vec<Seat> {
+    (0..n)
+        .map(|_| {
+            let seat = Seat::new(Box::new(crate::players::PlayerCPU::default()));
+            seat.set_currency(stack);
+            seat
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
+    use std::cmp::Ordering;
+
+    use poker::evaluate::FiveCardHandClass;
     use poker::{Card, cards};
+    use rand::{Rng, SeedableRng};
 
     use crate::{
-        game::{evaluator, show_eval_cards},
+        CU,
+        currency::Currency,
+        errors::PoksError,
+        game::{
+            Action, ActionOutcome, BoardByStreet, Cards, CardsDynamic, Deck, DeckKind, Game,
+            GameEvent, Phase, PlayerID, Pot, RNG, Winner, best_of, eval7, evaluator, full_deck,
+            show_cards_ascii, show_eval_cards, test_seats,
+        },
         len_to_const_arr,
+        lobby::Seat,
+        players::{PlayerCPU, PlayerState},
     };
 
+    fn hand(s: &str) -> Cards<2> {
+        let cards: Vec<Card> = s.split_whitespace().map(|c| c.parse().unwrap()).collect();
+        len_to_const_arr(&cards).unwrap()
+    }
+
+    #[test]
+    fn test_cards_duplicates() {
+        let mut cards: CardsDynamic = hand("Th 2c").into();
+        assert!(!cards.has_duplicates());
+        assert!(cards.validate_unique().is_ok());
+        assert!(!full_deck().has_duplicates());
+
+        cards.push("Th".parse().unwrap());
+        assert!(cards.has_duplicates());
+        assert!(matches!(
+            cards.validate_unique(),
+            Err(PoksError::DuplicateCard { card }) if card == "Th".parse::<Card>().unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_to_ascii_table() {
+        let seats: Vec<Seat> = ["Alice", "Bob", "Carol"]
+            .into_iter()
+            .map(|name| {
+                let seat = Seat::named(Box::new(PlayerCPU::default()), name);
+                seat.set_currency(CU!(100));
+                seat
+            })
+            .collect();
+        let mut game = Game::buid_with_seed(&seats, 0, [1; 32]).unwrap();
+        for (player, cards) in game.players.iter_mut().zip(["Ah Kd", "7c 7d", "2s 3h"]) {
+            player.set_hand(hand(cards));
+        }
+
+        assert_eq!(
+            game.to_ascii_table(Some(0)),
+            concat!(
+                "Pot: 1.50 | preflop\n",
+                "Board: -- -- -- -- --\n",
+                "> 0 D     Alice            100.00       0.00  Ah Kd  Playing\n",
+                "  1 SB    Bob               99.50       0.50  ?? ??  Playing\n",
+                "  2 BB    Carol             99.00       1.00  ?? ??  Playing\n",
+            )
+        );
+
+        // a spectator sees every hand
+        game.community_cards = board("5h 9h Jh").try_into().unwrap();
+        let spectator = game.to_ascii_table(None);
+        assert!(spectator.contains("Board: 5h 9h Jh -- --\n"));
+        for cards in ["Ah Kd", "7c 7d", "2s 3h"] {
+            assert!(spectator.contains(cards), "{spectator}");
+        }
+    }
+
+    #[test]
+    fn test_remaining_deck() {
+        let known = board("Ah Kh 2c 7d 9h Jc 3s");
+        let deck = remaining_deck(DeckKind::Standard, &known).unwrap();
+        assert_eq!(deck.len(), 45);
+        assert!(!deck.has_duplicates());
+        assert!(known.iter().all(|card| !deck.contains(card)));
+
+        // the twos and threes are not in a short deck in the first place
+        let short = remaining_deck(DeckKind::ShortDeck, &known).unwrap();
+        assert_eq!(short.len(), 31);
+        assert!(short.iter().all(|card| deck.contains(card)));
+
+        assert!(matches!(
+            remaining_deck(DeckKind::Standard, &board("Ah Kh Ah")),
+            Err(PoksError::DuplicateCard { .. })
+        ));
+    }
+
+    #[test]
+    fn test_cards_try_static_result() {
+        let six = board("2c 7d 9h Jc 3s Ah");
+        assert!(matches!(
+            six.clone().try_static_result::<7>(),
+            Err(PoksError::CardCountMismatch {
+                expected: 7,
+                actual: 6
+            })
+        ));
+        assert!(six.clone().try_static::<7>().is_none());
+        let cards: Cards<6> = six.try_static_result().unwrap();
+        assert_eq!(cards[5], "Ah".parse::<Card>().unwrap());
+    }
+
+    #[test]
+    fn test_process_no_action() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        let turn = game.turn();
+        let (log, events) = (game.gamelog().len(), game.events().len());
+        for _ in 0..1000 {
+            assert_eq!(
+                game.process_action(None).unwrap(),
+                ActionOutcome::AwaitingAction
+            );
+        }
+        // waiting for a decision changes nothing
+        assert_eq!(game.turn(), turn);
+        assert_eq!(game.gamelog().len(), log);
+        assert_eq!(game.events().len(), events);
+        assert!(game.action_history().is_empty());
+        assert!(game.gamelog().iter().all(|(pid, _)| pid.is_some()));
+    }
+
+    #[test]
+    fn test_remaining_to_act() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        game.set_phase(Phase::Flop);
+        assert_eq!(game.remaining_to_act(), 3);
+        assert_eq!(game.acted_this_street(), 0);
+
+        for remaining in [2, 1] {
+            let call = game.action_call();
+            game.process_action(Some(call)).unwrap();
+            assert_eq!(game.remaining_to_act(), remaining);
+            assert_eq!(game.acted_this_street(), 3 - remaining);
+        }
+    }
+
+    #[test]
+    fn test_last_action() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        assert_eq!(game.last_action(), None);
+
+        let first = game.turn();
+        game.process_action(Some(Action::Fold)).unwrap();
+        assert_eq!(game.last_action(), Some((first, Action::Fold)));
+
+        let second = game.turn();
+        let call = game.action_call();
+        game.process_action(Some(call)).unwrap();
+        assert_eq!(game.last_action(), Some((second, call)));
+
+        // not deciding is not an action
+        game.process_action(None).unwrap();
+        assert_eq!(game.last_action(), Some((second, call)));
+    }
+
+    #[test]
+    fn test_all_in_zero_is_illegal() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        let turn = game.turn();
+        assert!(matches!(
+            game.process_action(Some(Action::AllIn(CU!(0)))),
+            Err(PoksError::CannotActWithZeroStack { player_id }) if player_id == turn
+        ));
+        assert_eq!(game.turn(), turn);
+        assert_eq!(game.players[turn].state, PlayerState::Playing);
+    }
+
+    #[test]
+    fn test_zero_stack_is_folded() {
+        let seats = test_seats(3, CU!(5000));
+        // the dealer does not post a blind
+        seats[0].set_currency(CU!(0));
+        let mut game = Game::build(&seats, 0).unwrap();
+        assert_eq!(game.players[0].round_bet, CU!(0));
+        // even when the turn comes to the busted seat, it can not bet
+        game.turn = 0;
+        assert_eq!(
+            game.process_action(Some(Action::AllIn(CU!(0)))).unwrap(),
+            ActionOutcome::Skipped
+        );
+        assert!(!game.players[0].state.is_playing());
+        assert_ne!(game.turn(), 0);
+    }
+
+    #[test]
+    fn test_pot_odds_and_effective_stack() {
+        let seats = test_seats(3, CU!(5000));
+        seats[1].set_currency(CU!(300));
+        seats[2].set_currency(CU!(200));
+        let mut game = Game::build(&seats, 0).unwrap();
+        // 1,50 of blinds in the pot and the big blind to call
+        assert_eq!(game.turn(), 0);
+        assert!((game.pot_odds(0) - 0.4).abs() < 1e-9);
+        // the big blind has nothing to call
+        assert_eq!(game.pot_odds(2), 0.0);
+
+        // capped at the larger of the two short stacks, minus their blinds
+        assert_eq!(game.effective_stack(0), CU!(299, 50));
+        assert_eq!(game.effective_stack(2), CU!(199));
+
+        game.process_action(Some(Action::Fold)).unwrap();
+        assert_eq!(game.effective_stack(1), CU!(199));
+    }
+
+    #[test]
+    fn test_player_aggression() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        assert_eq!(game.player_aggression(1), 0.0);
+
+        game.action_history = vec![
+            (1, Action::Raise(CU!(10))),
+            (2, Action::Call(CU!(10))),
+            (1, Action::Call(CU!(5))),
+            (2, Action::check()),
+            (1, Action::Raise(CU!(20))),
+            (2, Action::Fold),
+        ];
+        assert_eq!(game.player_aggression(1), 2.0);
+        assert_eq!(game.player_aggression(2), 0.0);
+        assert_eq!(game.fold_percentage(1), 0.0);
+        assert!((game.fold_percentage(2) - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(game.fold_percentage(0), 0.0);
+
+        game.action_history.push((0, Action::AllIn(CU!(5000))));
+        assert_eq!(game.player_aggression(0), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_action_aggression() {
+        let mut actions = [
+            Action::AllIn(CU!(5)),
+            Action::Raise(CU!(1)),
+            Action::Call(CU!(100)),
+            Action::Check,
+            Action::Fold,
+        ];
+        actions.sort_by_key(Action::aggression_rank);
+        assert_eq!(
+            actions,
+            [
+                Action::Fold,
+                Action::Check,
+                Action::Call(CU!(100)),
+                Action::Raise(CU!(1)),
+                Action::AllIn(CU!(5)),
+            ]
+        );
+        // a big call commits more than a small raise, but is less aggressive
+        assert!(
+            Action::Call(CU!(100)).committed_amount() > Action::Raise(CU!(1)).committed_amount()
+        );
+        assert!(Action::Call(CU!(100)).aggression_rank() < Action::Raise(CU!(1)).aggression_rank());
+        assert_eq!(Action::Fold.committed_amount(), CU!(0));
+        assert_eq!(Action::Check.committed_amount(), CU!(0));
+        assert_eq!(Action::AllIn(CU!(5)).committed_amount(), CU!(5));
+    }
+
+    #[test]
+    fn test_action_from_command() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        let command = |game: &Game, s: &str| Action::from_command(s, game);
+
+        assert_eq!(command(&game, "fold").unwrap(), Action::Fold);
+        assert_eq!(command(&game, " CALL ").unwrap(), Action::Call(CU!(1)));
+        assert!(matches!(
+            command(&game, "check"),
+            Err(PoksError::InvalidCheck { .. })
+        ));
+        // raising by 20 on top of the big blind
+        assert_eq!(command(&game, "raise 20").unwrap(), Action::Raise(CU!(21)));
+        assert!(matches!(
+            command(&game, "raise 0,50"),
+            Err(PoksError::TooLowBetAmount { .. })
+        ));
+        assert_eq!(command(&game, "allin").unwrap(), Action::AllIn(CU!(5000)));
+
+        for invalid in ["", "bet 20", "raise", "fold 20", "raise 20 30"] {
+            assert!(
+                matches!(
+                    command(&game, invalid),
+                    Err(PoksError::InvalidCommand { .. })
+                ),
+                "{invalid:?}"
+            );
+        }
+        assert!(matches!(
+            command(&game, "raise lots"),
+            Err(PoksError::CurrencyParseError { .. })
+        ));
+
+        game.advance_phase().unwrap();
+        assert_eq!(command(&game, "check").unwrap(), Action::Check);
+        assert_eq!(command(&game, "call").unwrap(), Action::Check);
+    }
+
+    #[test]
+    fn test_hand_class() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        game.players[0].set_hand(hand("Ah 2h"));
+        game.players[1].set_hand(hand("Kc Kd"));
+        assert_eq!(game.hand_class(0), None);
+        assert_eq!(game.best_eval(0), None);
+
+        game.community_cards = board("5h 9h Jh").try_into().unwrap();
+        assert!(matches!(
+            game.hand_class(0),
+            Some(FiveCardHandClass::Flush { .. })
+        ));
+        assert!(matches!(
+            game.hand_class(1),
+            Some(FiveCardHandClass::Pair { .. })
+        ));
+        assert!(game.best_eval(0) > game.best_eval(1));
+        assert_eq!(game.hand_class(3), None);
+    }
+
+    #[test]
+    fn test_phase_str() {
+        for (phase, name) in [
+            (Phase::Preflop, "preflop"),
+            (Phase::Flop, "flop"),
+            (Phase::Turn, "turn"),
+            (Phase::River, "river"),
+        ] {
+            assert_eq!(phase.as_str(), name);
+            assert_eq!(name.parse::<Phase>().unwrap(), phase);
+        }
+        assert!(matches!(
+            "Flop".parse::<Phase>(),
+            Err(PoksError::InvalidPhase { .. })
+        ));
+    }
+
+    #[test]
+    fn test_uncalled_amount() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        // big blind is the only raise so far
+        assert_eq!(game.uncalled_amount(), Some((2, CU!(0, 50))));
+
+        game.players[0].round_bet = CU!(101);
+        game.players[1].state = PlayerState::Folded;
+        game.players[2].state = PlayerState::Folded;
+        assert_eq!(game.uncalled_amount(), Some((0, CU!(100))));
+        assert_eq!(game.contributed_by(0), CU!(101));
+        assert_eq!(game.contributed_by(2), CU!(1));
+
+        game.players[2].round_bet = CU!(101);
+        assert_eq!(game.uncalled_amount(), None);
+    }
+
+    #[test]
+    fn test_advance_turn_skips_folded() {
+        let mut game = Game::build(&test_seats(4, CU!(5000)), 0).unwrap();
+        let mut folded = Vec::new();
+        for _ in 0..2 {
+            folded.push(game.turn());
+            game.process_action(Some(Action::Fold)).unwrap();
+        }
+        let remaining: Vec<usize> = (0..4).filter(|pid| !folded.contains(pid)).collect();
+        assert_eq!(remaining.len(), 2);
+
+        let mut visited = Vec::new();
+        while !game.is_finished() && visited.len() < 20 {
+            visited.push(game.turn());
+            let call = game.action_call();
+            game.process_action(Some(call)).unwrap();
+        }
+        assert!(game.is_finished());
+        assert_eq!(game.phase(), Phase::River);
+        assert!(visited.iter().all(|pid| remaining.contains(pid)));
+        assert!(remaining.iter().all(|pid| visited.contains(pid)));
+    }
+
+    #[test]
+    fn test_perspective() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        game.advance_phase().unwrap();
+        let info = game.perspective(1);
+
+        assert_eq!(info.hero, 1);
+        assert_eq!(info.phase, Phase::Flop);
+        assert_eq!(info.community_cards, *game.community_cards().cards());
+        assert_eq!(info.community_cards.len(), 3);
+        assert_eq!(info.players.len(), 3);
+        assert_eq!(info.players[1].hand, Some(game.players[1].hand()));
+        assert_eq!(info.players[0].hand, None);
+        assert_eq!(info.players[2].hand, None);
+        assert_eq!(info.players[2].currency, game.players[2].currency());
+    }
+
+    #[test]
+    fn test_chips_required() {
+        let game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        // the small blind has 0,50 in, the big blind 1
+        assert_eq!(Action::Fold.chips_required(&game, 0), CU!(0));
+        assert_eq!(game.action_call().chips_required(&game, 0), CU!(1));
+        assert_eq!(Action::check().chips_required(&game, 1), CU!(0, 50));
+        assert_eq!(Action::check().chips_required(&game, 2), CU!(0));
+        assert_eq!(Action::Raise(CU!(10)).chips_required(&game, 2), CU!(10));
+        assert_eq!(
+            Action::AllIn(CU!(0)).chips_required(&game, 0),
+            game.players()[0].currency()
+        );
+    }
+
+    fn board(s: &str) -> CardsDynamic {
+        let cards: Vec<Card> = s.split_whitespace().map(|c| c.parse().unwrap()).collect();
+        cards.into()
+    }
+
+    /// Make the deck deal `draws` next, in that order, keeping the cards of the players out of it
+    fn stack_deck(game: &mut Game, draws: &str) {
+        let draws = board(draws);
+        let mut deck: Vec<Card> = full_deck()
+            .iter()
+            .filter(|c| !draws.contains(c))
+            .filter(|c| !game.players.iter().any(|p| p.hand().contains(c)))
+            .copied()
+            .collect();
+        // cards are drawn from the back
+        deck.extend(draws.iter().rev());
+        game.deck = Deck::new(deck.into());
+    }
+
+    /// Replace the bets of the hand: `(state, total bet)` for every player
+    fn set_bets(game: &mut Game, bets: &[(PlayerState, Currency)]) {
+        for (player, (state, bet)) in game.players.iter_mut().zip(bets) {
+            player.state = *state;
+            player.total_bet = *bet;
+            player.round_bet = CU!(0);
+        }
+    }
+
+    #[test]
+    fn test_compute_pots() {
+        let mut game = Game::build(&test_seats(4, CU!(5000)), 0).unwrap();
+        set_bets(
+            &mut game,
+            &[
+                (PlayerState::AllIn, CU!(10)),
+                (PlayerState::Playing, CU!(50)),
+                (PlayerState::Playing, CU!(50)),
+                (PlayerState::Folded, CU!(20)),
+            ],
+        );
+        let pots = game.compute_pots();
+        assert_eq!(
+            pots,
+            [
+                Pot {
+                    amount: CU!(40),
+                    eligible: vec![0, 1, 2]
+                },
+                Pot {
+                    amount: CU!(90),
+                    eligible: vec![1, 2]
+                },
+            ]
+        );
+        assert_eq!(pots.iter().map(|p| p.amount).sum::<Currency>(), game.pot());
+    }
+
+    #[test]
+    fn test_distribute_side_pots() {
+        let seats = test_seats(3, CU!(5000));
+        let mut game = Game::build(&seats, 0).unwrap();
+        set_bets(
+            &mut game,
+            &[
+                (PlayerState::AllIn, CU!(10)),
+                (PlayerState::Playing, CU!(50)),
+                (PlayerState::Playing, CU!(50)),
+            ],
+        );
+        game.players[0].set_hand(hand("Ah As"));
+        game.players[1].set_hand(hand("Kh Ks"));
+        game.players[2].set_hand(hand("Qh Qs"));
+        game.community_cards = board("2c 7d 9h Jc 3s").try_into().unwrap();
+        let before: Vec<Currency> = seats.iter().map(|s| s.currency()).collect();
+
+        game.distribute_pots().unwrap();
+        let winners: Vec<(PlayerID, Currency)> = game
+            .winners()
+            .iter()
+            .map(|w| (w.pid(), w.amount()))
+            .collect();
+        assert_eq!(winners, [(0, CU!(30)), (1, CU!(80))]);
+        assert_eq!(seats[0].currency(), before[0] + CU!(30));
+        assert_eq!(seats[1].currency(), before[1] + CU!(80));
+        assert_eq!(seats[2].currency(), before[2]);
+
+        // the biggest pot first
+        let mut by_amount = game.winners().to_vec();
+        by_amount.sort_by(|a, b| b.cmp_by_amount(a));
+        assert_eq!(by_amount[0].amount(), CU!(80));
+        assert_eq!(by_amount[1].amount(), CU!(30));
+        assert_eq!(by_amount[0].cmp_by_amount(&by_amount[0]), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_equal_flushes_split() {
+        let seats = test_seats(2, CU!(5000));
+        let mut game = Game::build(&seats, 0).unwrap();
+        set_bets(
+            &mut game,
+            &[
+                (PlayerState::Playing, CU!(10)),
+                (PlayerState::Playing, CU!(10)),
+            ],
+        );
+        // both play the flush on the board
+        game.players[0].set_hand(hand("Ac Qd"));
+        game.players[1].set_hand(hand("Ad Qc"));
+        game.community_cards = board("2h 5h 9h Jh Kh").try_into().unwrap();
+
+        game.distribute_pots().unwrap();
+        assert_eq!(game.winners().len(), 1);
+        assert_eq!(game.winners()[0].shares(), [(1, CU!(10)), (0, CU!(10))]);
+    }
+
+    #[test]
+    fn test_equal_straights_split() {
+        let seats = test_seats(3, CU!(5000));
+        let mut game = Game::build(&seats, 0).unwrap();
+        set_bets(
+            &mut game,
+            &[
+                (PlayerState::Playing, CU!(10)),
+                (PlayerState::Folded, CU!(0, 1)),
+                (PlayerState::Playing, CU!(10)),
+            ],
+        );
+        game.players[0].set_hand(hand("Ah Kd"));
+        game.players[2].set_hand(hand("As Kc"));
+        game.community_cards = board("5c 6d 7h 8s 9c").try_into().unwrap();
+        let before: Vec<Currency> = seats.iter().map(|s| s.currency()).collect();
+
+        game.distribute_pots().unwrap();
+        let winner = game.winner().unwrap();
+        assert!(matches!(winner, Winner::Split(..)));
+        // the odd cent goes to the first player left of the dealer
+        assert_eq!(winner.shares(), [(2, CU!(10, 1)), (0, CU!(10))]);
+        assert_eq!(winner.amount(), game.pot());
+        assert_eq!(seats[0].currency(), before[0] + CU!(10));
+        assert_eq!(seats[2].currency(), before[2] + CU!(10, 1));
+    }
+
+    #[test]
+    fn test_check_down_to_showdown() {
+        let mut game = Game::build(&test_seats(2, CU!(5000)), 0).unwrap();
+        game.players[0].set_hand(hand("Ah As"));
+        game.players[1].set_hand(hand("7c 2d"));
+        stack_deck(&mut game, "2s Kh 8d 3c 4s Jd 5s 9c");
+
+        let winner = game.check_down_to_showdown().unwrap();
+        assert_eq!(game.phase(), Phase::River);
+        assert_eq!(*game.community_cards().cards(), board("Kh 8d 3c Jd 9c"));
+        assert_eq!(winner.pid(), 0);
+        assert_eq!(winner.amount(), game.pot());
+        assert!(game.check_down_to_showdown().is_err());
+    }
+
+    #[test]
+    fn test_deck_integrity_over_many_games() {
+        for (i, kind) in [DeckKind::Standard, DeckKind::ShortDeck]
+            .into_iter()
+            .cycle()
+            .take(100)
+            .enumerate()
+        {
+            let seed = [i as u8; 32];
+            let mut game =
+                Game::build_with_deck_and_seed(&test_seats(6, CU!(5000)), 0, kind, seed).unwrap();
+            game.assert_deck_integrity();
+            game.check_down_to_showdown().unwrap();
+            game.assert_deck_integrity();
+            assert_eq!(game.burned.len(), 3);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "deck integrity violated")]
+    fn test_deck_integrity_duplicate() {
+        let mut game = Game::build(&test_seats(2, CU!(5000)), 0).unwrap();
+        let card = game.players[0].hand()[0];
+        game.players[1].set_hand([card, game.players[1].hand()[1]]);
+        game.assert_deck_integrity();
+    }
+
+    #[test]
+    fn test_seed_hex() {
+        let mut seed = [0u8; 32];
+        for (i, byte) in seed.iter_mut().enumerate() {
+            *byte = (i * 37) as u8;
+        }
+        let hex = Game::seed_to_hex(&seed);
+        assert_eq!(hex.len(), 64);
+        assert!(hex.starts_with("00254a6f"));
+        assert_eq!(Game::from_seed_str(&hex).unwrap(), seed);
+        assert_eq!(Game::from_seed_str(&hex.to_uppercase()).unwrap(), seed);
+
+        let game = Game::buid_with_seed(&test_seats(2, CU!(5000)), 0, seed).unwrap();
+        assert_eq!(game.current_seed(), seed);
+
+        assert!(Game::from_seed_str("abc").is_err());
+        assert!(matches!(
+            Game::from_seed_str(&hex.replace('a', "x")),
+            Err(PoksError::InvalidSeed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_settled_pot() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        assert_eq!(game.settled_pot(), CU!(0));
+        assert_eq!(game.current_street_bets(), game.pot());
+
+        while game.phase() == Phase::Preflop {
+            let call = game.action_call();
+            game.process_action(Some(call)).unwrap();
+        }
+        let preflop = game.pot();
+        assert_eq!(game.settled_pot(), preflop);
+        assert_eq!(game.current_street_bets(), CU!(0));
+
+        game.process_action(Some(Action::Raise(CU!(5)))).unwrap();
+        assert_eq!(game.phase(), Phase::Flop);
+        assert_eq!(game.settled_pot(), preflop);
+        assert_eq!(game.current_street_bets(), CU!(5));
+        assert_eq!(game.settled_pot() + game.current_street_bets(), game.pot());
+    }
+
+    #[test]
+    fn test_verbose_log() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        let call = game.action_call();
+        game.process_action(Some(call)).unwrap();
+        let (_, line) = game.gamelog().last().unwrap();
+        assert!(!line.contains("stack"));
+
+        game.set_verbose_log(true);
+        let pid = game.turn();
+        game.process_action(Some(Action::Raise(CU!(5)))).unwrap();
+        let (who, line) = game.gamelog().last().unwrap();
+        // the small blind had 0,50 in and raised by 5 more
+        assert_eq!(pid, 1);
+        assert_eq!(*who, Some(pid));
+        assert!(line.contains(&format!("stack {}", CU!(4994, 50))), "{line}");
+        assert!(
+            line.contains(&format!("round bet {}", CU!(5, 50))),
+            "{line}"
+        );
+    }
+
+    #[test]
+    fn test_clone_with_rng() {
+        let game = Game::buid_with_seed(&test_seats(3, CU!(5000)), 0, [7; 32]).unwrap();
+        let mut a = game.clone_with_rng(RNG::from_seed([1; 32]));
+        let mut b = game.clone_with_rng(RNG::from_seed([2; 32]));
+        assert_eq!(a.deck(), b.deck());
+        assert_eq!(a.current_seed(), b.current_seed());
+
+        let draws_a: Vec<u64> = (0..4).map(|_| a.rng_mut().r#gen()).collect();
+        let draws_b: Vec<u64> = (0..4).map(|_| b.rng_mut().r#gen()).collect();
+        assert_ne!(draws_a, draws_b);
+
+        // a plain clone continues the same sequence
+        let mut c = a.clone();
+        let draws_a: Vec<u64> = (0..4).map(|_| a.rng_mut().r#gen()).collect();
+        let draws_c: Vec<u64> = (0..4).map(|_| c.rng_mut().r#gen()).collect();
+        assert_eq!(draws_a, draws_c);
+    }
+
+    #[test]
+    fn test_too_many_players() {
+        assert!(matches!(
+            Game::build(&test_seats(30, CU!(5000)), 0),
+            Err(PoksError::TooManyPlayers {
+                requested: 30,
+                max: 22
+            })
+        ));
+        assert!(matches!(
+            Game::build_with_deck(&test_seats(15, CU!(5000)), 0, DeckKind::ShortDeck),
+            Err(PoksError::TooManyPlayers {
+                requested: 15,
+                max: 14
+            })
+        ));
+        let mut game = Game::build(&test_seats(22, CU!(5000)), 0).unwrap();
+        game.check_down_to_showdown().unwrap();
+    }
+
+    #[test]
+    fn test_clone_for_simulation() {
+        let seats = test_seats(3, CU!(5000));
+        let game = Game::buid_with_seed(&seats, 0, [7; 32]).unwrap();
+        let deck = game.deck().clone();
+        let stacks: Vec<Currency> = seats.iter().map(|s| s.currency()).collect();
+
+        let mut sim = game.clone_for_simulation([1; 32], None);
+        assert_eq!(sim.current_seed(), [1; 32]);
+        assert_ne!(sim.deck(), game.deck());
+        assert_eq!(sim.players()[0].hand(), game.players()[0].hand());
+        sim.check_down_to_showdown().unwrap();
+
+        assert_eq!(game.deck(), &deck);
+        assert_eq!(game.phase(), Phase::Preflop);
+        assert!(game.community_cards().is_empty());
+        let after: Vec<Currency> = seats.iter().map(|s| s.currency()).collect();
+        assert_eq!(after, stacks);
+    }
+
+    #[test]
+    fn test_player_rng() {
+        let mut game = Game::buid_with_seed(&test_seats(3, CU!(5000)), 0, [7; 32]).unwrap();
+        let draw = |game: &Game, pid| game.player_rng(pid).r#gen::<u64>();
+        assert_eq!(draw(&game, 0), draw(&game, 0));
+        assert_ne!(draw(&game, 0), draw(&game, 1));
+
+        let before = draw(&game, game.turn());
+        let call = game.action_call();
+        game.process_action(Some(call)).unwrap();
+        assert_ne!(draw(&game, game.turn()), before);
+    }
+
+    #[test]
+    fn test_validate_action() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        let turn = game.turn();
+        let call = game.action_call();
+        assert!(game.validate_action(turn, call).is_ok());
+        assert!(game.validate_action(turn, Action::Raise(CU!(10))).is_ok());
+        assert!(matches!(
+            game.validate_action(turn, Action::Call(CU!(3))),
+            Err(PoksError::CallAmountMismatch { .. })
+        ));
+        assert!(matches!(
+            game.validate_action((turn + 1) % 3, call),
+            Err(PoksError::NotPlayersTurn { .. })
+        ));
+        assert!(matches!(
+            game.validate_action(7, call),
+            Err(PoksError::InvalidPlayerId { .. })
+        ));
+
+        game.process_action(Some(Action::Raise(CU!(10)))).unwrap(); // raises to 10 by 9
+        let before = game.clone();
+        assert!(matches!(
+            game.validate_action(game.turn(), Action::Raise(CU!(15))),
+            Err(PoksError::TooLowBetAmount { .. })
+        ));
+        // nothing changed
+        assert_eq!(game.turn(), before.turn());
+        assert_eq!(game.pot(), before.pot());
+        assert!(
+            game.validate_action(game.turn(), game.action_call())
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_events() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        assert_eq!(
+            game.take_events(),
+            [
+                GameEvent::BlindPosted(1, CU!(0, 50)),
+                GameEvent::BlindPosted(2, CU!(1)),
+            ]
+        );
+
+        game.process_action(Some(Action::Fold)).unwrap();
+        game.process_action(Some(Action::Call(CU!(0, 50)))).unwrap();
+        game.process_action(Some(Action::check())).unwrap();
+        let flop: CardsDynamic = game.community_cards()[..3].into();
+        assert_eq!(
+            game.events(),
+            [
+                GameEvent::Acted(0, Action::Fold),
+                GameEvent::Acted(1, Action::Call(CU!(0, 50))),
+                GameEvent::Acted(2, Action::check()),
+                GameEvent::CardsDealt(flop),
+                GameEvent::PhaseChanged(Phase::Flop),
+            ]
+        );
+
+        game.check_down_to_showdown().unwrap();
+        let events = game.take_events();
+        assert!(game.events().is_empty());
+        let winners: Vec<GameEvent> = game
+            .winners()
+            .iter()
+            .cloned()
+            .map(GameEvent::Winner)
+            .collect();
+        assert!(events.ends_with(&winners));
+        assert_eq!(
+            events[events.len() - winners.len() - 1],
+            GameEvent::Showdown
+        );
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| matches!(e, GameEvent::CardsDealt(_)))
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_payout_touches_only_winners() {
+        let seats = test_seats(3, CU!(5000));
+        let game = Game::build(&seats, 0).unwrap();
+        let before: Vec<Currency> = seats.iter().map(|s| s.currency()).collect();
+
+        Winner::UnknownCards(CU!(10), 1).payout(&game).unwrap();
+        assert_eq!(seats[0].currency(), before[0]);
+        assert_eq!(seats[1].currency(), before[1] + CU!(10));
+        assert_eq!(seats[2].currency(), before[2]);
+
+        seats[2].set_currency(Currency::from(u64::MAX));
+        assert!(matches!(
+            Winner::UnknownCards(CU!(10), 2).payout(&game),
+            Err(PoksError::CurrencyOverflow)
+        ));
+        assert_eq!(seats[2].currency(), Currency::from(u64::MAX));
+    }
+
+    #[test]
+    fn test_payout_rejects_invalid_winners() {
+        let seats = test_seats(3, CU!(5000));
+        let mut game = Game::build(&seats, 0).unwrap();
+        let before: Vec<Currency> = seats.iter().map(|s| s.currency()).collect();
+        let cards: Vec<Card> = "Th Tc 3c 4c 5c 7h 8h"
+            .split_whitespace()
+            .map(|c| c.parse().unwrap())
+            .collect();
+        let eval = evaluator().evaluate_five(&cards[..]).unwrap();
+        let seven: Cards<7> = cards.try_into().unwrap();
+
+        assert!(matches!(
+            Winner::UnknownCards(CU!(0), 1).payout(&game),
+            Err(PoksError::TransactionError { .. })
+        ));
+        assert!(matches!(
+            Winner::Split(vec![(0, CU!(5), seven), (3, CU!(5), seven)], eval).payout(&game),
+            Err(PoksError::InvalidPlayerId { player_id: 3, .. })
+        ));
+        let after: Vec<Currency> = seats.iter().map(|s| s.currency()).collect();
+        assert_eq!(after, before);
+
+        assert!(game.set_winner(Winner::UnknownCards(CU!(0), 1)).is_err());
+        assert!(!game.is_finished());
+    }
+
+    #[test]
+    fn test_board_by_street() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        assert_eq!(game.board_by_street(), BoardByStreet::default());
+
+        game.advance_phase().unwrap();
+        game.advance_phase().unwrap();
+        assert_eq!(game.phase(), Phase::Turn);
+        let board = game.board_by_street();
+        let cards = game.community_cards();
+        assert_eq!(board.flop.unwrap()[..], cards[..3]);
+        assert_eq!(board.turn, Some(cards[3]));
+        assert_eq!(board.river, None);
+        assert_eq!(board.cards(), cards[..]);
+    }
+
+    #[test]
+    fn test_post_blinds() {
+        let seats = test_seats(3, CU!(5000));
+        seats[2].set_currency(CU!(0, 30));
+        let game = Game::build(&seats, 0).unwrap();
+
+        // the small blind pays its blind, the big blind is all in for less
+        assert_eq!(seats[1].currency(), CU!(4999, 50));
+        assert_eq!(game.players()[1].round_bet(), CU!(0, 50));
+        assert_eq!(seats[2].currency(), CU!(0));
+        assert_eq!(game.players()[2].round_bet(), CU!(0, 30));
+        assert_eq!(game.players()[2].state(), PlayerState::AllIn);
+
+        let seats = test_seats(3, CU!(5000));
+        let game = Game::build(&seats, 0).unwrap();
+        assert_eq!(seats[2].currency(), CU!(4999));
+        assert_eq!(game.players()[2].round_bet(), CU!(1));
+        assert_eq!(game.players()[2].state(), PlayerState::Playing);
+    }
+
+    #[test]
+    fn test_stack_in_bb() {
+        let mut game = Game::build(&test_seats(3, CU!(5000)), 0).unwrap();
+        game.big_blind = CU!(100);
+        // player 0 is the dealer and has not posted a blind
+        assert_eq!(game.stack_in_bb(0), 50.0);
+        assert_eq!(game.bb_count(CU!(250)), 2.5);
+        assert_eq!(game.pot_in_bb(), game.pot().in_blinds(CU!(100)));
+    }
+
+    #[test]
+    fn test_all_in_count() {
+        let mut game = Game::build(&test_seats(4, CU!(5000)), 0).unwrap();
+        assert_eq!(game.all_in_count(), 0);
+        assert!(!game.is_all_in(3));
+
+        game.players[3].state = PlayerState::AllIn;
+        assert!(game.is_all_in(3));
+        assert!(!game.is_all_in(0));
+        assert_eq!(game.all_in_count(), 1);
+    }
+
+    #[test]
+    fn test_all_in_equities() {
+        let mut game = Game::buid_with_seed(&test_seats(2, CU!(5000)), 0, [7; 32]).unwrap();
+        assert!(game.all_in_equities().is_err());
+
+        game.players[0].set_hand(hand("Ah As"));
+        game.players[1].set_hand(hand("Kd Kc"));
+        for player in game.players.iter_mut() {
+            player.state = PlayerState::AllIn;
+        }
+        let equities = game.all_in_equities().unwrap();
+        assert_eq!(equities.len(), 2);
+        assert_eq!(equities[0].0, 0);
+        assert!(
+            (0.75..0.88).contains(&equities[0].1),
+            "aces should have about 80% equity against kings, got {}",
+            equities[0].1
+        );
+        assert!((equities[0].1 + equities[1].1 - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_show_cards_ascii() {
+        assert_eq!(show_cards_ascii(&hand("Th Td")), "Th Td");
+        assert_eq!(show_cards_ascii(&board("As 2c 9h")), "As 2c 9h");
+        assert_eq!(show_cards_ascii(&[]), "");
+    }
+
     #[test]
     fn test_show_eval_cards() {
         let r: Vec<(Vec<_>, &str)> = vec![
@@ -748,4 +2920,39 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_eval7() {
+        let seven = |s: &str| -> Cards<7> {
+            let cards: Vec<Card> = s.split_whitespace().map(|c| c.parse().unwrap()).collect();
+            len_to_const_arr(&cards).unwrap()
+        };
+        // from high card to straight flush, every hand beats the one before it
+        let hands: Vec<Cards<7>> = [
+            "Th 2c 3c 4c 5c 7h 8h",
+            "Th Tc 3c 4c 5c 7h 8h",
+            "Th Tc 3c 3h 5c 7h 8h",
+            "Th Tc Td 5c 6h 7h 8h",
+            "Th 3c 4c 5c 6h 7h 8h",
+            "9h 3c 4h 5h 6h 7h 8h",
+        ]
+        .into_iter()
+        .map(seven)
+        .collect();
+        for pair in hands.windows(2) {
+            assert!(eval7(&pair[0]) < eval7(&pair[1]));
+        }
+        // a flush beats a straight
+        assert!(eval7(&seven("2h 5h 9h Jh Kh 3c 4d")) > eval7(&seven("5c 6d 7h 8s 9c 2d 2s")));
+        // the same hand in other suits ties
+        assert_eq!(
+            eval7(&seven("Ah Kd 5c 6d 7h 8s 9c")),
+            eval7(&seven("As Kc 5d 6h 7s 8c 9d"))
+        );
+        // the straight flush wins
+        assert_eq!(best_of(&hands), 5);
+        assert_eq!(best_of(&hands[..2]), 1);
+        // equal hands go to the first of them
+        assert_eq!(best_of(&[hands[3], hands[3]]), 0);
+    }
 }
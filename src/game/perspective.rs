@@ -0,0 +1,64 @@
+//! What a single player can know about a game
+
+use crate::currency::Currency;
+use crate::game::{Action, Cards, CardsDynamic, Game, Phase, PlayerID};
+use crate::players::PlayerState;
+
+/// Everything a single player can know about a [`Game`]
+///
+/// In game theoretic terms this is the information set of the player: the public state of the
+/// table plus their own hole cards. The hole cards of the opponents and the deck are left out,
+/// which makes this suitable as training data for bots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InfoSet {
+    pub hero: PlayerID,
+    pub phase: Phase,
+    pub turn: PlayerID,
+    pub dealer: PlayerID,
+    pub small_blind: Currency,
+    pub big_blind: Currency,
+    pub community_cards: CardsDynamic,
+    pub players: Vec<PlayerView>,
+    pub actions: Vec<(PlayerID, Action)>,
+}
+
+/// A player as seen by someone at the table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerView {
+    /// Only known for the player this view was made for
+    pub hand: Option<Cards<2>>,
+    pub state: PlayerState,
+    pub currency: Currency,
+    pub round_bet: Currency,
+    pub total_bet: Currency,
+}
+
+impl Game {
+    /// The game as the given player sees it
+    #[must_use]
+    pub fn perspective(&self, pid: PlayerID) -> InfoSet {
+        let players = self
+            .players
+            .iter()
+            .enumerate()
+            .map(|(other, player)| PlayerView {
+                hand: (other == pid).then(|| player.hand()),
+                state: player.state(),
+                currency: player.currency(),
+                round_bet: player.round_bet(),
+                total_bet: player.total_bet(),
+            })
+            .collect();
+        InfoSet {
+            hero: pid,
+            phase: self.phase,
+            turn: self.turn,
+            dealer: self.dealer,
+            small_blind: self.small_blind,
+            big_blind: self.big_blind,
+            community_cards: self.community_cards.cards().clone(),
+            players,
+            actions: self.action_history.clone(),
+        }
+    }
+}
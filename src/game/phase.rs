@@ -0,0 +1,85 @@
+//! The streets of a hand
+
+/// A street of a hand, named after the community cards dealt on it
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Phase {
+    #[default]
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl Phase {
+    /// A stable lowercase name, independent of the order of the variants
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Phase::Preflop => "preflop",
+            Phase::Flop => "flop",
+            Phase::Turn => "turn",
+            Phase::River => "river",
+        }
+    }
+
+    /// The street after this one, [`None`] after the river, when the showdown comes
+    #[must_use]
+    pub const fn next(self) -> Option<Phase> {
+        match self {
+            Phase::Preflop => Some(Phase::Flop),
+            Phase::Flop => Some(Phase::Turn),
+            Phase::Turn => Some(Phase::River),
+            Phase::River => None,
+        }
+    }
+
+    /// The street before this one, [`None`] before the flop
+    #[must_use]
+    pub const fn previous(self) -> Option<Phase> {
+        match self {
+            Phase::Preflop => None,
+            Phase::Flop => Some(Phase::Preflop),
+            Phase::Turn => Some(Phase::Flop),
+            Phase::River => Some(Phase::Turn),
+        }
+    }
+
+    /// How many community cards are on the table during this street
+    #[must_use]
+    pub const fn board_len(self) -> usize {
+        match self {
+            Phase::Preflop => 0,
+            Phase::Flop => 3,
+            Phase::Turn => 4,
+            Phase::River => 5,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::Phase;
+
+    #[test]
+    fn test_phase_chain() {
+        let mut chain = vec![Phase::Preflop];
+        while let Some(next) = chain.last().unwrap().next() {
+            chain.push(next);
+        }
+        assert_eq!(
+            chain,
+            [Phase::Preflop, Phase::Flop, Phase::Turn, Phase::River]
+        );
+        let board: Vec<usize> = chain.iter().map(|p| p.board_len()).collect();
+        assert_eq!(board, [0, 3, 4, 5]);
+
+        for pair in chain.windows(2) {
+            assert_eq!(pair[1].previous(), Some(pair[0]));
+        }
+        assert_eq!(Phase::Preflop.previous(), None);
+    }
+}
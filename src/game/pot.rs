@@ -0,0 +1,303 @@
+//! Main pot and side pots
+
+use poker::{Eval, Evaluator, FiveCard};
+
+use crate::Result;
+use crate::currency::Currency;
+use crate::errors::PoksError;
+use crate::game::{Cards, Game, PlayerID, Winner, evaluator};
+use crate::players::{PlayerState, ShowPreference};
+
+/// Chips that the eligible players compete for
+///
+/// The first pot of a hand is the main pot. A player who went all in can only win what the others
+/// matched of their bet, everything above that goes into side pots they are not eligible for.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Pot {
+    pub amount: Currency,
+    pub eligible: Vec<PlayerID>,
+}
+
+impl Game {
+    /// Split everything that was bet in this hand into the main pot and the side pots
+    ///
+    /// The pots are ordered from the main pot to the last side pot. Bets of folded players end
+    /// up in the pots they reached, but folded players are not eligible for any of them.
+    #[must_use]
+    pub fn compute_pots(&self) -> Vec<Pot> {
+        let bets: Vec<Currency> = self.players.iter().map(|p| p.total_bet()).collect();
+        let mut levels: Vec<Currency> = self
+            .players
+            .iter()
+            .filter(|p| p.state == PlayerState::AllIn)
+            .map(|p| p.total_bet())
+            .collect();
+        levels.extend(bets.iter().max().copied());
+        levels.sort();
+        levels.dedup();
+
+        let mut pots: Vec<Pot> = Vec::new();
+        let mut previous = Currency::ZERO;
+        for level in levels {
+            let amount: Currency = bets
+                .iter()
+                .map(|bet| (*bet).min(level) - (*bet).min(previous))
+                .sum();
+            let eligible: Vec<PlayerID> = (0..self.players.len())
+                .filter(|pid| self.players[*pid].state.is_playing() && bets[*pid] >= level)
+                .collect();
+            previous = level;
+            match pots.last_mut() {
+                // nobody new competes for these chips, they belong to the pot below
+                Some(last) if eligible.is_empty() || last.eligible == eligible => {
+                    last.amount += amount
+                }
+                _ => pots.push(Pot { amount, eligible }),
+            }
+        }
+        pots
+    }
+
+    /// Give every player back what they bet in this hand, leaving the pot empty
+    ///
+    /// This is for hands that are abandoned before they were settled.
+    pub(crate) fn refund_bets(&mut self) -> Result<()> {
+        for player in &mut self.players {
+            player.seat.add_currency(player.total_bet())?;
+            player.total_bet = Currency::ZERO;
+            player.round_bet = Currency::ZERO;
+        }
+        Ok(())
+    }
+
+    /// Award every pot to the best hand among the players eligible for it
+    pub(crate) fn distribute_pots(&mut self) -> Result<()> {
+        let evaluator = evaluator();
+        let mut winners = Vec::new();
+        for mut pot in self.compute_pots() {
+            // a pot only one player can win is their uncalled bet, it goes back unraked
+            if pot.eligible.len() > 1 {
+                pot.amount = self.take_rake(pot.amount);
+            }
+            winners.push(self.award_pot(evaluator, &pot)?);
+        }
+        for winner in winners {
+            self.set_winner(winner)?;
+        }
+        Ok(())
+    }
+
+    /// Find the winner of `pot` with the current board, without paying anything out
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::CardCountMismatch`] if the board is not complete.
+    pub(crate) fn award_pot(&self, evaluator: &Evaluator, pot: &Pot) -> Result<Winner> {
+        let mut evals: Vec<(PlayerID, Eval<FiveCard>, Cards<7>)> = pot
+            .eligible
+            .iter()
+            .map(|pid| self.showdown_eval(evaluator, *pid))
+            .collect::<Result<_>>()?;
+        evals.sort_by(|a, b| b.1.cmp(&a.1));
+        // only the hand strength counts, equally strong hands split the pot
+        let best = evals[0].1;
+        let mut tied: Vec<_> = evals.into_iter().take_while(|e| e.1 == best).collect();
+        if tied.len() == 1 {
+            let (pid, eval, cards) = tied[0];
+            return Ok(Winner::KnownCards(pot.amount, pid, eval, cards));
+        }
+        // the indivisible cents go to the first player left of the dealer
+        let n = self.players.len();
+        tied.sort_by_key(|(pid, ..)| (pid + n - self.dealer - 1) % n);
+        let (share, mut remainder) = pot.amount.split_evenly(tied.len());
+        let mut shares = Vec::with_capacity(tied.len());
+        for (pid, _, cards) in tied {
+            shares.push((pid, share + remainder, cards));
+            remainder = Currency::ZERO;
+        }
+        Ok(Winner::Split(shares, best))
+    }
+
+    /// The hands that were shown at the showdown, ordered by seat
+    ///
+    /// See [`Game::should_reveal`] for who shows. Hands that were won without a showdown are not
+    /// shown at all.
+    #[must_use]
+    pub fn revealed_hands(&self) -> Vec<(PlayerID, Cards<2>)> {
+        let shown = self.showdown_reveals();
+        self.players
+            .iter()
+            .enumerate()
+            .filter(|(pid, _)| shown.contains(pid))
+            .map(|(pid, player)| (pid, player.hand()))
+            .collect()
+    }
+
+    /// Whether the player `pid` shows their hand at the showdown
+    ///
+    /// Nobody shows if the hand was won without a showdown. Otherwise, the last player who bet
+    /// or raised on the river shows first, or the first player left of the dealer if nobody
+    /// did. Going around the table from there, a player shows if their hand is at least as
+    /// strong as the best one shown so far or if they won a share of a pot. Beaten hands are
+    /// mucked, unless the seat is set to [`ShowPreference::AlwaysShow`].
+    #[must_use]
+    pub fn should_reveal(&self, pid: PlayerID) -> bool {
+        self.showdown_reveals().contains(&pid)
+    }
+
+    /// The players who show their hand at the showdown, in the order they show it
+    fn showdown_reveals(&self) -> Vec<PlayerID> {
+        if self.winners.is_empty()
+            || self
+                .winners
+                .iter()
+                .any(|w| matches!(w, Winner::UnknownCards(..)))
+        {
+            return Vec::new();
+        }
+        let won: Vec<PlayerID> = self
+            .winners
+            .iter()
+            .flat_map(|w| w.shares())
+            .map(|(pid, _)| pid)
+            .collect();
+        let n = self.players.len();
+        let first = self
+            .last_aggressor
+            .filter(|pid| self.players[*pid].state.is_playing())
+            .unwrap_or((self.dealer + 1) % n);
+        let evaluator = evaluator();
+        let mut best: Option<Eval<FiveCard>> = None;
+        let mut shown = Vec::new();
+        for pid in (0..n).map(|offset| (first + offset) % n) {
+            let player = &self.players[pid];
+            if !player.state.is_playing() {
+                continue;
+            }
+            let Ok((_, eval, _)) = self.showdown_eval(evaluator, pid) else {
+                continue; // nothing to show without a complete board
+            };
+            if best.is_none_or(|best| eval >= best)
+                || won.contains(&pid)
+                || player.seat.show_preference() == ShowPreference::AlwaysShow
+            {
+                best = best.max(Some(eval));
+                shown.push(pid);
+            }
+        }
+        shown
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`PoksError::CardCountMismatch`] if the board is not complete.
+    fn showdown_eval(
+        &self,
+        evaluator: &Evaluator,
+        pid: PlayerID,
+    ) -> Result<(PlayerID, Eval<FiveCard>, Cards<7>)> {
+        let hand_plus_table: Cards<7> = self.hand_plus_table(pid).try_static_result()?;
+        let eval = evaluator
+            .evaluate_five(hand_plus_table)
+            .map_err(|e| PoksError::card_evaluation(format!("{e:?}")))?;
+        Ok((pid, eval, hand_plus_table))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use poker::{Eval, FiveCard};
+
+    use crate::CU;
+    use crate::game::{Action, Game, Winner, evaluator, test_seats};
+    use crate::lobby::RakeConfig;
+    use crate::players::ShowPreference;
+
+    #[test]
+    fn test_showdown_finds_best_hand() {
+        let seats = test_seats(6, CU!(5000));
+        for i in 0..20 {
+            let mut game = Game::buid_with_seed(&seats, 0, [i; 32]).unwrap();
+            game.check_down_to_showdown().unwrap();
+
+            // evaluate every hand on its own
+            let best: Eval<FiveCard> = (0..seats.len())
+                .map(|pid| {
+                    evaluator()
+                        .evaluate_five(&*game.hand_plus_table(pid))
+                        .unwrap()
+                })
+                .max()
+                .unwrap();
+            let eval = match game.winner().unwrap() {
+                Winner::KnownCards(_, _, eval, _) | Winner::Split(_, eval) => eval,
+                Winner::UnknownCards(..) => panic!("the hand went to showdown"),
+            };
+            assert_eq!(eval, best);
+        }
+    }
+
+    #[test]
+    fn test_uncontested_win_reveals_nothing() {
+        let seats = test_seats(3, CU!(5000));
+        for seat in &seats {
+            seat.set_show_preference(ShowPreference::AlwaysShow);
+        }
+        let mut game = Game::build(&seats, 0).unwrap();
+        while !game.is_finished() {
+            game.process_action(Some(Action::Fold)).unwrap();
+        }
+        assert!(matches!(game.winner(), Some(Winner::UnknownCards(..))));
+        assert!(game.revealed_hands().is_empty());
+        assert!((0..3).all(|pid| !game.should_reveal(pid)));
+    }
+
+    #[test]
+    fn test_revealed_hands() {
+        let seats = test_seats(2, CU!(5000));
+        seats[1].set_show_preference(ShowPreference::AlwaysShow);
+        assert_eq!(seats[0].show_preference(), ShowPreference::AutoMuck);
+
+        let (mut always_show_lost, mut default_lost) = (false, false);
+        for i in 0..40 {
+            let mut game = Game::buid_with_seed(&seats, 0, [i; 32]).unwrap();
+            let winner = game.check_down_to_showdown().unwrap();
+            let revealed: Vec<usize> = game.revealed_hands().iter().map(|(pid, _)| *pid).collect();
+            match winner {
+                Winner::KnownCards(_, 0, ..) => {
+                    // the always show seat lost, but shows anyway
+                    assert_eq!(revealed, [0, 1]);
+                    always_show_lost = true;
+                }
+                Winner::KnownCards(_, 1, ..) => {
+                    // the default seat lost and mucks
+                    assert_eq!(revealed, [1]);
+                    default_lost = true;
+                }
+                _ => assert_eq!(revealed, [0, 1]),
+            }
+        }
+        assert!(always_show_lost && default_lost);
+    }
+
+    #[test]
+    fn test_uncalled_excess_is_not_raked() {
+        let seats = test_seats(2, CU!(5000));
+        let mut game = Game::build(&seats, 0).unwrap();
+        game.set_rake(Some(RakeConfig::new(5, CU!(1000)).unwrap()));
+        let (big, short) = (game.turn(), 1 - game.turn());
+        // the short stack has 100 in the hand, blind included
+        let posted = game.players[short].total_bet();
+        seats[short].set_currency(CU!(100) - posted);
+
+        let stack = game.players[big].currency();
+        game.process_action(Some(Action::AllIn(stack))).unwrap();
+        let stack = game.players[short].currency();
+        game.process_action(Some(Action::AllIn(stack))).unwrap();
+
+        assert!(game.is_finished());
+        // 5% of the 200 both played for, nothing of the 4900 the big stack gets back
+        assert_eq!(game.rake(), CU!(10));
+        assert_eq!(seats[big].currency() + seats[short].currency(), CU!(5090));
+    }
+}
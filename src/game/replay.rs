@@ -0,0 +1,157 @@
+//! Recording a hand and playing it back
+
+use crate::Result;
+use crate::currency::Currency;
+use crate::errors::PoksError;
+use crate::game::{Action, ActionOutcome, BettingLimit, DeckKind, Game, PlayerID, Seed};
+use crate::lobby::{RakeConfig, Seat};
+use crate::players::PlayerCPU;
+
+/// Everything needed to deal a hand again and take the same actions in it
+///
+/// Record one with [`Game::record_replay`] or
+/// [`Lobby::record_replay`](crate::lobby::Lobby::record_replay).
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Replay {
+    pub seed: Seed,
+    pub deck_kind: DeckKind,
+    pub dealer: PlayerID,
+    /// The stacks of the seats before the blinds were posted, one per seat
+    pub starting_stacks: Vec<Currency>,
+    pub small_blind: Currency,
+    pub big_blind: Currency,
+    pub limit: BettingLimit,
+    pub rake: Option<RakeConfig>,
+    pub run_it: u8,
+    /// Seats that paid a big blind they missed
+    pub dead_blinds: Vec<PlayerID>,
+    pub actions: Vec<(PlayerID, Action)>,
+    /// The stacks of the seats when the replay was recorded
+    pub final_stacks: Vec<Currency>,
+}
+
+impl Game {
+    /// Record the hand so far, so it can be played again with [`Replay::playback`]
+    ///
+    /// The stacks the seats started with are worked out from what they bet and won in this
+    /// game, so the currency of the seats must not have been changed from outside meanwhile.
+    #[must_use]
+    pub fn record_replay(&self) -> Replay {
+        let final_stacks: Vec<Currency> = self.players.iter().map(|p| p.currency()).collect();
+        let mut starting_stacks: Vec<Currency> = self
+            .players
+            .iter()
+            .zip(&final_stacks)
+            .map(|(player, stack)| *stack + player.total_bet())
+            .collect();
+        for (pid, amount) in self.winners.iter().flat_map(|w| w.shares()) {
+            starting_stacks[pid] -= amount;
+        }
+        Replay {
+            seed: self.seed,
+            deck_kind: self.deck_kind,
+            dealer: self.dealer,
+            starting_stacks,
+            small_blind: self.small_blind,
+            big_blind: self.big_blind,
+            limit: self.limit,
+            rake: self.rake,
+            run_it: self.run_it,
+            dead_blinds: self.dead_blinds.clone(),
+            actions: self.action_history.clone(),
+            final_stacks,
+        }
+    }
+}
+
+impl Replay {
+    /// Deal the recorded hand again and take the recorded actions
+    ///
+    /// The seats of the returned game are computer players, but they are never asked to act.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::ReplayMismatch`] if a recorded action was taken out of turn or the
+    /// stacks do not end up like they were recorded, and the error of [`Game::process_action`]
+    /// if a recorded action can not be taken.
+    pub fn playback(&self) -> Result<Game> {
+        let seats: Vec<Seat> = self
+            .starting_stacks
+            .iter()
+            .map(|stack| {
+                let seat = Seat::new(Box::new(PlayerCPU::default()));
+                seat.set_currency(*stack);
+                seat
+            })
+            .collect();
+        let mut game = Game::build_with_blinds(
+            &seats,
+            self.dealer,
+            self.deck_kind,
+            self.seed,
+            self.small_blind,
+            self.big_blind,
+        )?;
+        game.set_rake(self.rake);
+        game.set_betting_limit(self.limit)?;
+        game.set_run_it(self.run_it);
+        for pid in &self.dead_blinds {
+            game.post_missed_blind(*pid)?;
+        }
+
+        for (pid, action) in &self.actions {
+            // players who can not act are passed over without an action
+            while game.process_action(None)? == ActionOutcome::Skipped {}
+            if game.turn() != *pid || game.is_finished() {
+                return Err(PoksError::ReplayMismatch {
+                    reason: format!("player {pid} acts out of turn"),
+                });
+            }
+            game.process_action(Some(*action))?;
+        }
+
+        let stacks: Vec<Currency> = seats.iter().map(Seat::currency).collect();
+        if stacks != self.final_stacks {
+            return Err(PoksError::ReplayMismatch {
+                reason: "the stacks differ".to_string(),
+            });
+        }
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CU;
+    use crate::game::Action;
+    use crate::lobby::Lobby;
+    use crate::players::Strategy;
+
+    #[test]
+    fn test_replay_cpu_hand() {
+        let mut builder = Lobby::builder();
+        for name in ["A", "B", "C", "D"] {
+            builder
+                .cpu_named(name, CU!(5000), Strategy::LooseAggressive)
+                .unwrap();
+        }
+        let mut lobby = builder.build_with_seed([3; 32]).unwrap();
+        while !lobby.game.is_finished() {
+            lobby.tick_game().unwrap();
+        }
+
+        let replay = lobby.record_replay();
+        assert_eq!(replay.starting_stacks, [CU!(5000); 4]);
+        let game = replay.playback().unwrap();
+        assert!(game.is_finished());
+        assert_eq!(game.winners(), lobby.game.winners());
+        assert_eq!(game.community_cards(), lobby.game.community_cards());
+
+        // another action in the middle of the hand is noticed
+        let mut broken = replay.clone();
+        let (pid, _) = broken.actions[0];
+        broken.actions.insert(1, (pid, Action::Fold));
+        assert!(broken.playback().is_err());
+    }
+}
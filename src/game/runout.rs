@@ -0,0 +1,160 @@
+//! Running the rest of the board more than once when everyone is all in
+
+use std::iter::successors;
+
+use crate::Result;
+use crate::currency::Currency;
+use crate::errors::PoksError;
+use crate::game::{Board, CardsDynamic, Game, GameEvent, Phase, Pot, Winner, evaluator};
+use crate::players::PlayerState;
+
+impl Game {
+    /// How often the board is run out once all players are all in
+    #[must_use]
+    pub fn run_it(&self) -> u8 {
+        self.run_it
+    }
+
+    /// Run the rest of the board `times` times once all players are all in
+    ///
+    /// Each runout wins its share of every pot. `0` and `1` both mean that the board is dealt
+    /// once, like in any other hand.
+    pub fn set_run_it(&mut self, times: u8) {
+        self.run_it = times.max(1);
+    }
+
+    /// The cards of every runout after the first one
+    ///
+    /// Only the cards that were dealt after the players went all in are included, the first
+    /// runout is the regular [`Game::community_cards`].
+    #[must_use]
+    pub fn extra_runouts(&self) -> &[CardsDynamic] {
+        &self.extra_runouts
+    }
+
+    /// Whether no more betting can happen and the rest of the board only needs to be dealt
+    pub(crate) fn is_all_in_runout(&self) -> bool {
+        !self.is_finished()
+            && self.community_cards.len() < Board::MAX_CARDS
+            && self.players.iter().filter(|p| p.state.is_playing()).count() >= 2
+            && self
+                .players
+                .iter()
+                .filter(|p| p.state == PlayerState::Playing)
+                .count()
+                <= 1
+    }
+
+    /// Deal the rest of the board `times` times and split every pot evenly between the runouts
+    ///
+    /// The indivisible cents of a pot go to the first runout. Afterwards, the first runout is on
+    /// the table and the others are in [`Game::extra_runouts`]. Returns the winners of all pots
+    /// of all runouts, these are also available through [`Game::winners`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::GameFinished`] if the hand is already settled,
+    /// [`PoksError::BettingNotClosed`] if a player can still bet and
+    /// [`PoksError::InsufficientCards`] if the deck does not hold enough cards for every runout.
+    pub fn run_it_twice(&mut self, times: u8) -> Result<Vec<Winner>> {
+        if self.is_finished() {
+            return Err(PoksError::GameFinished);
+        }
+        if !self.is_all_in_runout() || !self.is_betting_complete() {
+            return Err(PoksError::BettingNotClosed);
+        }
+        let streets: Vec<Phase> = successors(self.phase.next(), |phase| phase.next()).collect();
+        let base = self.community_cards.clone();
+        let per_run = streets.len() + Board::MAX_CARDS - base.len();
+        let runs = usize::from(times.max(1));
+        if self.deck.len() < per_run * runs {
+            return Err(PoksError::InsufficientCards);
+        }
+
+        let mut pots = self.compute_pots();
+        for pot in pots.iter_mut().filter(|pot| pot.eligible.len() > 1) {
+            pot.amount = self.take_rake(pot.amount);
+        }
+        let evaluator = evaluator();
+        let mut winners = Vec::new();
+        let mut boards = Vec::with_capacity(runs);
+        for run in 0..runs {
+            self.community_cards = base.clone();
+            for street in &streets {
+                self.burn_card();
+                let dealt = self.community_cards.len();
+                while self.community_cards.len() < street.board_len() {
+                    self.add_table_card()?;
+                }
+                self.events
+                    .push(GameEvent::CardsDealt(self.community_cards[dealt..].into()));
+                if run == 0 {
+                    self.set_phase(*street);
+                }
+            }
+            for pot in &pots {
+                let (mut share, remainder) = pot.amount.split_evenly(runs);
+                if run == 0 {
+                    share += remainder;
+                }
+                if share == Currency::ZERO {
+                    continue;
+                }
+                let pot = Pot {
+                    amount: share,
+                    eligible: pot.eligible.clone(),
+                };
+                winners.push(self.award_pot(evaluator, &pot)?);
+            }
+            boards.push(self.community_cards.clone());
+        }
+
+        let mut boards = boards.into_iter();
+        self.community_cards = boards.next().expect("the board is run at least once");
+        self.extra_runouts = boards.map(|board| board[base.len()..].into()).collect();
+        self.events.push(GameEvent::Showdown);
+        for winner in &winners {
+            self.set_winner(winner.clone())?;
+        }
+        #[cfg(debug_assertions)]
+        self.assert_deck_integrity();
+        Ok(winners)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CU;
+    use crate::currency::Currency;
+    use crate::game::{Action, Game, Phase, test_seats};
+
+    #[test]
+    fn test_run_it_twice() {
+        let seats = test_seats(2, CU!(100));
+        let mut game = Game::build(&seats, 0).unwrap();
+        game.set_run_it(2);
+        assert_eq!(game.run_it(), 2);
+
+        let stack = game.players[game.turn()].currency();
+        game.process_action(Some(Action::AllIn(stack))).unwrap();
+        let call = game.action_call();
+        game.process_action(Some(call)).unwrap();
+
+        assert!(game.is_finished());
+        assert_eq!(game.phase(), Phase::River);
+        assert_eq!(game.community_cards().len(), 5);
+        assert_eq!(game.extra_runouts().len(), 1);
+        assert_eq!(game.extra_runouts()[0].len(), 5);
+        // one pot, split between the two runouts
+        assert_eq!(game.winners().len(), 2);
+        let paid: Currency = game
+            .winners()
+            .iter()
+            .flat_map(|w| w.shares())
+            .map(|(_, amount)| amount)
+            .sum();
+        assert_eq!(paid, CU!(200));
+        assert_eq!(paid, game.pot());
+        assert_eq!(seats[0].currency() + seats[1].currency(), CU!(200));
+    }
+}
@@ -0,0 +1,434 @@
+//! Snapshots of a [`Game`] with serde
+//!
+//! A [`Game`] is (de)serialized through a plain representation of its state. After
+//! deserialization, the players are no longer connected to the seats of a lobby, they sit in
+//! detached seats that hold their hand and currency. The random number generator is saved with
+//! its position in the stream, so a restored game draws the same numbers the original would
+//! have. The [events](crate::game::GameEvent) of the game are not part of a snapshot.
+
+use poker::{Card, Eval, FiveCard};
+use rand::SeedableRng;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::currency::Currency;
+use crate::errors::PoksError;
+use crate::game::{
+    Action, BettingLimit, Board, Cards, CardsDynamic, Deck, DeckKind, Game, GameState, GlogItem,
+    Phase, Player, PlayerID, RNG, Seed, Winner, evaluator, show_cards_ascii,
+};
+use crate::lobby::{RakeConfig, Seat};
+use crate::players::{PlayerBehavior, PlayerLocal, PlayerState};
+use crate::{Result, err_int};
+
+impl Serialize for CardsDynamic {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(|card| show_cards_ascii(&[*card])))
+    }
+}
+
+impl<'de> Deserialize<'de> for CardsDynamic {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let cards: Vec<String> = Vec::deserialize(deserializer)?;
+        cards
+            .iter()
+            .map(|card| {
+                card.parse::<Card>()
+                    .map_err(|e| format!("invalid card {card:?}: {e:?}"))
+            })
+            .collect::<std::result::Result<Vec<Card>, String>>()
+            .map(Into::into)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct PlayerRepr {
+    state: PlayerState,
+    total_bet: Currency,
+    round_bet: Currency,
+    acted: bool,
+    can_raise: bool,
+    hand: CardsDynamic,
+    currency: Currency,
+}
+
+/// The community cards, tagged with the street they were dealt on
+#[derive(Serialize, Deserialize)]
+struct BoardRepr {
+    flop: CardsDynamic,
+    turn: CardsDynamic,
+    river: CardsDynamic,
+}
+
+/// Evaluations are left out, they are computed from the cards again
+#[derive(Serialize, Deserialize)]
+enum WinnerRepr {
+    UnknownCards(Currency, PlayerID),
+    KnownCards(Currency, PlayerID, CardsDynamic),
+    Split(Vec<(PlayerID, Currency, CardsDynamic)>),
+}
+
+/// Where the random number generator of a game stands
+#[derive(Serialize, Deserialize)]
+struct RngRepr {
+    seed: Seed,
+    stream: u64,
+    word_pos: u128,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct GameRepr {
+    phase: Phase,
+    turn: PlayerID,
+    dealer: PlayerID,
+    players: Vec<PlayerRepr>,
+    board: BoardRepr,
+    winners: Vec<WinnerRepr>,
+    deck: Deck,
+    burned: CardsDynamic,
+    state: GameState,
+    small_blind: Currency,
+    big_blind: Currency,
+    game_log: Vec<GlogItem>,
+    verbose_log: bool,
+    #[serde(default)]
+    rake: Option<RakeConfig>,
+    #[serde(default)]
+    rake_taken: Currency,
+    #[serde(default = "run_once")]
+    run_it: u8,
+    #[serde(default)]
+    extra_runouts: Vec<CardsDynamic>,
+    #[serde(default)]
+    dead_blinds: Vec<PlayerID>,
+    #[serde(default)]
+    limit: BettingLimit,
+    #[serde(default)]
+    bets_this_street: u8,
+    deck_kind: DeckKind,
+    last_action: Option<(PlayerID, Action)>,
+    last_aggressor: Option<PlayerID>,
+    min_raise: Currency,
+    action_history: Vec<(PlayerID, Action)>,
+    seed: Seed,
+    /// Snapshots without it start the generator from the seed again
+    #[serde(default)]
+    rng: Option<RngRepr>,
+}
+
+impl From<&Player> for PlayerRepr {
+    fn from(player: &Player) -> Self {
+        Self {
+            state: player.state,
+            total_bet: player.total_bet,
+            round_bet: player.round_bet,
+            acted: player.acted,
+            can_raise: player.can_raise,
+            hand: player.hand().into(),
+            currency: player.currency(),
+        }
+    }
+}
+
+impl TryFrom<PlayerRepr> for Player {
+    type Error = PoksError;
+
+    fn try_from(repr: PlayerRepr) -> Result<Self> {
+        let hand: Cards<2> = repr.hand.try_static_result()?;
+        let mut behavior = PlayerLocal::new();
+        behavior.set_currency(repr.currency);
+        let mut player = Player::new(hand, Seat::new(Box::new(behavior)));
+        player.state = repr.state;
+        player.total_bet = repr.total_bet;
+        player.round_bet = repr.round_bet;
+        player.acted = repr.acted;
+        player.can_raise = repr.can_raise;
+        Ok(player)
+    }
+}
+
+impl From<&Board> for BoardRepr {
+    fn from(board: &Board) -> Self {
+        let board = board.by_street();
+        Self {
+            flop: board.flop.map(CardsDynamic::from).unwrap_or_default(),
+            turn: board.turn.into_iter().collect::<Vec<_>>().into(),
+            river: board.river.into_iter().collect::<Vec<_>>().into(),
+        }
+    }
+}
+
+impl TryFrom<BoardRepr> for Board {
+    type Error = PoksError;
+
+    fn try_from(repr: BoardRepr) -> Result<Self> {
+        let (flop, turn, river) = (repr.flop.len(), repr.turn.len(), repr.river.len());
+        let valid = matches!(
+            (flop, turn, river),
+            (0, 0, 0) | (3, 0, 0) | (3, 1, 0) | (3, 1, 1)
+        );
+        if !valid {
+            return Err(err_int!(
+                "Invalid board with {flop} flop, {turn} turn and {river} river cards"
+            ));
+        }
+        let mut cards = repr.flop;
+        cards.extend(repr.turn.iter());
+        cards.extend(repr.river.iter());
+        Board::try_from(cards)
+    }
+}
+
+impl From<&Winner> for WinnerRepr {
+    fn from(winner: &Winner) -> Self {
+        match winner {
+            Winner::UnknownCards(amount, pid) => Self::UnknownCards(*amount, *pid),
+            Winner::KnownCards(amount, pid, _, cards) => {
+                Self::KnownCards(*amount, *pid, (*cards).into())
+            }
+            Winner::Split(shares, _) => Self::Split(
+                shares
+                    .iter()
+                    .map(|(pid, amount, cards)| (*pid, *amount, (*cards).into()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Snapshots from before games could be run more than once
+fn run_once() -> u8 {
+    1
+}
+
+fn evaluate(cards: &[Card]) -> Result<Eval<FiveCard>> {
+    evaluator()
+        .evaluate_five(cards)
+        .map_err(|e| PoksError::card_evaluation(format!("{e:?}")))
+}
+
+impl From<&RNG> for RngRepr {
+    fn from(rng: &RNG) -> Self {
+        Self {
+            seed: rng.get_seed(),
+            stream: rng.get_stream(),
+            word_pos: rng.get_word_pos(),
+        }
+    }
+}
+
+impl From<RngRepr> for RNG {
+    fn from(repr: RngRepr) -> Self {
+        let mut rng = RNG::from_seed(repr.seed);
+        rng.set_stream(repr.stream);
+        rng.set_word_pos(repr.word_pos);
+        rng
+    }
+}
+
+impl WinnerRepr {
+    fn into_winner(self) -> Result<Winner> {
+        Ok(match self {
+            Self::UnknownCards(amount, pid) => Winner::UnknownCards(amount, pid),
+            Self::KnownCards(amount, pid, cards) => {
+                let eval = evaluate(&cards)?;
+                let cards: Cards<7> = cards.try_static_result()?;
+                Winner::KnownCards(amount, pid, eval, cards)
+            }
+            Self::Split(shares) => {
+                let mut eval = None;
+                let mut evaluated = Vec::with_capacity(shares.len());
+                for (pid, amount, cards) in shares {
+                    let share_eval = evaluate(&cards)?;
+                    if eval.is_some_and(|eval| eval != share_eval) {
+                        return Err(err_int!("Player {pid} has a different hand in a split pot"));
+                    }
+                    eval = Some(share_eval);
+                    evaluated.push((pid, amount, cards.try_static_result()?));
+                }
+                let eval = eval.ok_or_else(|| err_int!("A split pot needs at least one share"))?;
+                Winner::Split(evaluated, eval)
+            }
+        })
+    }
+}
+
+impl From<Game> for GameRepr {
+    fn from(game: Game) -> Self {
+        Self {
+            players: game.players.iter().map(PlayerRepr::from).collect(),
+            winners: game.winners.iter().map(WinnerRepr::from).collect(),
+            phase: game.phase,
+            turn: game.turn,
+            dealer: game.dealer,
+            board: BoardRepr::from(&game.community_cards),
+            deck: game.deck,
+            burned: game.burned,
+            state: game.state,
+            small_blind: game.small_blind,
+            big_blind: game.big_blind,
+            game_log: game.game_log,
+            verbose_log: game.verbose_log,
+            rake: game.rake,
+            rake_taken: game.rake_taken,
+            run_it: game.run_it,
+            extra_runouts: game.extra_runouts,
+            dead_blinds: game.dead_blinds,
+            limit: game.limit,
+            bets_this_street: game.bets_this_street,
+            deck_kind: game.deck_kind,
+            last_action: game.last_action,
+            last_aggressor: game.last_aggressor,
+            min_raise: game.min_raise,
+            action_history: game.action_history,
+            seed: game.seed,
+            rng: Some(RngRepr::from(&game.rng)),
+        }
+    }
+}
+
+impl TryFrom<GameRepr> for Game {
+    type Error = PoksError;
+
+    fn try_from(repr: GameRepr) -> Result<Self> {
+        let players = repr
+            .players
+            .into_iter()
+            .map(Player::try_from)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Game {
+            phase: repr.phase,
+            turn: repr.turn,
+            dealer: repr.dealer,
+            players,
+            community_cards: repr.board.try_into()?,
+            deck: repr.deck,
+            burned: repr.burned,
+            state: repr.state,
+            small_blind: repr.small_blind,
+            big_blind: repr.big_blind,
+            game_log: repr.game_log,
+            verbose_log: repr.verbose_log,
+            events: Vec::new(),
+            rake: repr.rake,
+            rake_taken: repr.rake_taken,
+            run_it: repr.run_it,
+            extra_runouts: repr.extra_runouts,
+            dead_blinds: repr.dead_blinds,
+            limit: repr.limit,
+            bets_this_street: repr.bets_this_street,
+            deck_kind: repr.deck_kind,
+            last_action: repr.last_action,
+            last_aggressor: repr.last_aggressor,
+            min_raise: repr.min_raise,
+            action_history: repr.action_history,
+            seed: repr.seed,
+            rng: repr
+                .rng
+                .map_or_else(|| RNG::from_seed(repr.seed), RNG::from),
+            winners: repr
+                .winners
+                .into_iter()
+                .map(WinnerRepr::into_winner)
+                .collect::<Result<Vec<_>>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use poker::Card;
+    use rand::Rng;
+
+    use crate::CU;
+    use crate::game::{Action, Cards, Game, Phase, Winner, evaluator, test_seats};
+
+    fn roundtrip(game: &Game) -> Game {
+        let json = serde_json::to_string(game).unwrap();
+        let restored: Game = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+        restored
+    }
+
+    #[test]
+    fn test_snapshot_mid_hand() {
+        let mut game = Game::buid_with_seed(&test_seats(3, CU!(5000)), 0, [3; 32]).unwrap();
+        let call = game.action_call();
+        game.process_action(Some(call)).unwrap();
+        game.process_action(Some(Action::Raise(CU!(5)))).unwrap();
+
+        let restored = roundtrip(&game);
+        assert_eq!(restored.turn(), game.turn());
+        assert_eq!(restored.phase(), game.phase());
+        assert_eq!(restored.pot(), game.pot());
+        assert_eq!(restored.deck(), game.deck());
+        assert_eq!(restored.last_action(), game.last_action());
+        for (a, b) in restored.players().iter().zip(game.players()) {
+            assert_eq!(a.hand(), b.hand());
+            assert_eq!(a.currency(), b.currency());
+            assert_eq!(a.round_bet(), b.round_bet());
+        }
+    }
+
+    #[test]
+    fn test_snapshot_rng_position() {
+        let mut game = Game::buid_with_seed(&test_seats(3, CU!(5000)), 0, [5; 32]).unwrap();
+        for _ in 0..7 {
+            game.rng_mut().r#gen::<u64>();
+        }
+
+        let mut restored = roundtrip(&game);
+        for _ in 0..16 {
+            assert_eq!(
+                restored.rng_mut().r#gen::<u64>(),
+                game.rng_mut().r#gen::<u64>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_snapshot_board_by_street() {
+        let mut game = Game::buid_with_seed(&test_seats(3, CU!(5000)), 0, [4; 32]).unwrap();
+        while game.phase() != Phase::Turn {
+            let call = game.action_call();
+            game.process_action(Some(call)).unwrap();
+        }
+        let json: serde_json::Value = serde_json::to_value(&game).unwrap();
+        assert_eq!(json["board"]["flop"].as_array().unwrap().len(), 3);
+        assert_eq!(json["board"]["turn"].as_array().unwrap().len(), 1);
+        assert!(json["board"]["river"].as_array().unwrap().is_empty());
+
+        let restored = roundtrip(&game);
+        assert_eq!(restored.board_by_street(), game.board_by_street());
+    }
+
+    #[test]
+    fn test_snapshot_finished() {
+        let mut game = Game::buid_with_seed(&test_seats(4, CU!(5000)), 1, [9; 32]).unwrap();
+        game.check_down_to_showdown().unwrap();
+
+        let restored = roundtrip(&game);
+        assert!(restored.is_finished());
+        assert_eq!(restored.winners(), game.winners());
+    }
+
+    #[test]
+    fn test_snapshot_split_hands() {
+        let mut game = Game::buid_with_seed(&test_seats(3, CU!(5000)), 0, [6; 32]).unwrap();
+        let seven = |cards: &str| -> Cards<7> {
+            let cards: Vec<Card> = cards
+                .split_whitespace()
+                .map(|c| c.parse().unwrap())
+                .collect();
+            cards.try_into().unwrap()
+        };
+        let a = seven("Ah Kd 5c 6d 7h 8s 9c");
+        let b = seven("As Kc 5c 6d 7h 8s 9c");
+        let eval = evaluator().evaluate_five(&a[..]).unwrap();
+        game.winners
+            .push(Winner::Split(vec![(2, CU!(10), b), (0, CU!(10), a)], eval));
+
+        let restored = roundtrip(&game);
+        assert_eq!(restored.winners(), game.winners());
+    }
+}
@@ -0,0 +1,99 @@
+use crate::Result;
+use crate::currency::Currency;
+use crate::errors::PoksError;
+use crate::game::{DEFAULT_BIG_BLIND, DEFAULT_SMALL_BLIND};
+
+/// Blinds that grow with the number of games played in a lobby
+///
+/// Every level is `(first game, small blind, big blind)`. The games of a lobby are counted from 1,
+/// a game is played with the last level whose first game it has reached.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct BlindSchedule {
+    levels: Vec<(u64, Currency, Currency)>,
+}
+
+impl BlindSchedule {
+    /// Create a schedule from its levels
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::ConfigError`] if there are no levels, the first level does not start
+    /// at game 1, the levels are not ordered by their first game, or a level has a big blind of
+    /// zero or a small blind above the big blind.
+    pub fn new(levels: Vec<(u64, Currency, Currency)>) -> Result<Self> {
+        let err = |reason: String| PoksError::ConfigError {
+            field: "blind schedule".to_string(),
+            reason,
+        };
+        match levels.first() {
+            None => return Err(err("there are no levels".to_string())),
+            Some((start, ..)) if *start != 1 => {
+                return Err(err(format!("the first level starts at game {start}")));
+            }
+            _ => (),
+        }
+        if levels.windows(2).any(|w| w[0].0 >= w[1].0) {
+            return Err(err("levels are not ordered by their first game".to_string()));
+        }
+        for (start, small, big) in &levels {
+            if *big == Currency::ZERO || small > big {
+                return Err(err(format!(
+                    "invalid blinds {small}/{big} for the level at game {start}"
+                )));
+            }
+        }
+        Ok(Self { levels })
+    }
+
+    /// The small and big blind for the game with the number `game`
+    ///
+    /// There is no game 0, it gets the blinds of the first level like game 1.
+    #[must_use]
+    pub fn blinds_for(&self, game: u64) -> (Currency, Currency) {
+        let (_, small, big) = self
+            .levels
+            .iter()
+            .rev()
+            .find(|(start, ..)| *start <= game)
+            .unwrap_or(&self.levels[0]);
+        (*small, *big)
+    }
+
+    pub fn levels(&self) -> &[(u64, Currency, Currency)] {
+        &self.levels
+    }
+}
+
+impl Default for BlindSchedule {
+    /// The same blinds forever
+    fn default() -> Self {
+        Self {
+            levels: vec![(1, DEFAULT_SMALL_BLIND, DEFAULT_BIG_BLIND)],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CU;
+    use crate::lobby::BlindSchedule;
+
+    #[test]
+    fn test_blind_schedule() {
+        let schedule =
+            BlindSchedule::new(vec![(1, CU!(0, 50), CU!(1)), (5, CU!(1), CU!(2))]).unwrap();
+        assert_eq!(schedule.blinds_for(0), (CU!(0, 50), CU!(1)));
+        assert_eq!(schedule.blinds_for(1), (CU!(0, 50), CU!(1)));
+        assert_eq!(schedule.blinds_for(4), (CU!(0, 50), CU!(1)));
+        assert_eq!(schedule.blinds_for(5), (CU!(1), CU!(2)));
+        assert_eq!(schedule.blinds_for(500), (CU!(1), CU!(2)));
+
+        assert!(BlindSchedule::new(vec![]).is_err());
+        // games are counted from 1
+        assert!(BlindSchedule::new(vec![(0, CU!(1), CU!(2))]).is_err());
+        assert!(BlindSchedule::new(vec![(2, CU!(1), CU!(2))]).is_err());
+        assert!(BlindSchedule::new(vec![(1, CU!(1), CU!(2)), (1, CU!(2), CU!(4))]).is_err());
+        assert!(BlindSchedule::new(vec![(1, CU!(3), CU!(2))]).is_err());
+        assert_eq!(BlindSchedule::default().levels()[0].0, 1);
+    }
+}
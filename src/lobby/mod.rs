@@ -1,27 +1,70 @@
 use circular_queue::CircularQueue;
+use rand::{Rng, SeedableRng};
 use std::fmt::Debug;
-use tracing::trace;
+use std::time::{Duration, Instant};
+use tracing::{Span, debug, info_span, trace, warn};
 
 use crate::Result;
+use crate::currency::Currency;
 use crate::errors::PoksError;
-use crate::game::{Game, PlayerID};
+use crate::game::{ActionOutcome, BettingLimit, DeckKind, Game, PlayerID, RNG, Replay, Seed};
+use crate::players::local::ActionAccessor;
+use crate::players::{PlayerBehavior, PlayerCPU, PlayerLocal, PlayerState, Strategy};
 
+mod blinds;
+mod rake;
+mod runner;
 mod seat;
+mod stats;
+pub use blinds::*;
+pub use rake::*;
+pub use runner::*;
 pub use seat::*;
+pub use stats::*;
 
 pub const ACTION_LOG_SIZE: usize = 2000;
 
-#[derive(Debug)]
+/// Called by [`Lobby::tick_game`] with the game that was just finished
+pub type HandFinishedHook = Box<dyn FnMut(&Game)>;
+
 pub struct Lobby {
     players: Vec<Seat>,
     pub game: Game,
     action_log: CircularQueue<(Option<PlayerID>, String)>,
     games_played: u64,
+    /// The dealer of the current game, the button moves on from here
+    dealer: PlayerID,
+    /// Seats that were out while the big blind passed them, they owe it when they return
+    missed_blinds: Vec<PlayerID>,
+    deck_kind: DeckKind,
+    blinds: BlindSchedule,
+    rake: Option<RakeConfig>,
+    total_rake: Currency,
+    limit: BettingLimit,
+    seed: Option<Seed>,
+    local_players: Vec<(PlayerID, ActionAccessor)>,
+    hand_finished_hook: Option<HandFinishedHook>,
+    stats: Vec<PlayerStats>,
+    /// Everything that happens in the current game is logged inside this span
+    hand_span: Span,
+    turn_deadline: Option<Duration>,
+    /// The game number, the turn and the length of the action history when the player whose
+    /// turn it is was first asked to act, and when that was
+    turn_started: Option<((u64, PlayerID, usize), Instant)>,
 }
 
 #[derive(Debug, Default)]
 pub struct LobbyBuilder {
     pub players: Vec<Seat>,
+    deck_kind: DeckKind,
+    blinds: BlindSchedule,
+    rake: Option<RakeConfig>,
+    limit: BettingLimit,
+    local_players: Vec<(PlayerID, ActionAccessor)>,
+    rebuy_placeholders: Vec<PlayerID>,
+    seed: Option<Seed>,
+    starting_stack: Option<Currency>,
+    turn_deadline: Option<Duration>,
 }
 
 impl LobbyBuilder {
@@ -36,16 +79,169 @@ impl LobbyBuilder {
         Ok(self)
     }
 
+    /// Add a player that is controlled through an [`ActionAccessor`]
+    ///
+    /// The accessors of all local players are available with [`Lobby::local_players`], so input
+    /// can be routed to whichever of them has to act.
+    pub fn add_local_player(&mut self) -> Result<&mut Self> {
+        let player = PlayerLocal::new();
+        self.local_players
+            .push((self.players.len(), player.action_field_reference()));
+        self.add_player(Box::new(player))
+    }
+
+    /// Add a player that may start without currency, it sits out until it buys in
+    ///
+    /// Other players without currency are rejected by [`build`](Self::build).
+    pub fn add_rebuy_placeholder(&mut self, player: BehaveBox) -> Result<&mut Self> {
+        self.rebuy_placeholders.push(self.players.len());
+        self.add_player(player)
+    }
+
+    /// Add a named CPU player with the given starting currency and play style
+    pub fn cpu_named(
+        &mut self,
+        name: impl Into<String>,
+        currency: Currency,
+        strategy: Strategy,
+    ) -> Result<&mut Self> {
+        let mut player = PlayerCPU::new(strategy);
+        player.set_currency(currency);
+        self.players.push(Seat::named(Box::new(player), name));
+
+        Ok(self)
+    }
+
+    /// Add `n` CPU players with the default play style, each starting with `starting_cash`
+    pub fn add_cpu_players(&mut self, n: usize, starting_cash: Currency) -> Result<&mut Self> {
+        for _ in 0..n {
+            let mut player = PlayerCPU::default();
+            player.set_currency(starting_cash);
+            self.add_player(Box::new(player))?;
+        }
+
+        Ok(self)
+    }
+
+    /// Give every seat that has no currency yet `stack` when the lobby is built
+    ///
+    /// Rebuy placeholders are left without currency.
+    pub fn with_starting_stack(&mut self, stack: Currency) -> &mut Self {
+        self.starting_stack = Some(stack);
+        self
+    }
+
+    /// Give players `deadline` to decide, see [`Lobby::set_turn_deadline`]
+    pub fn turn_deadline(&mut self, deadline: Duration) -> &mut Self {
+        self.turn_deadline = Some(deadline);
+        self
+    }
+
+    /// Play every game of the lobby with this kind of deck
+    pub fn deck(&mut self, kind: DeckKind) -> &mut Self {
+        self.deck_kind = kind;
+        self
+    }
+
+    /// Raise the blinds of the games in the lobby according to `schedule`
+    pub fn blinds(&mut self, schedule: BlindSchedule) -> &mut Self {
+        self.blinds = schedule;
+        self
+    }
+
+    /// Play every game of the lobby with this betting structure
+    pub fn betting_limit(&mut self, limit: BettingLimit) -> &mut Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Take a commission from the pots of the games in the lobby
+    pub fn rake(&mut self, rake: RakeConfig) -> &mut Self {
+        self.rake = Some(rake);
+        self
+    }
+
+    /// Make the whole session reproducible from a single number
+    ///
+    /// The master seed of the lobby is derived from `seed`. Every game is dealt from a seed
+    /// derived from the master seed, and computer players draw their decisions from
+    /// [`Game::player_rng`], so two lobbies with the same players and seed play exactly the same
+    /// session. [`build_with_seed`](Self::build_with_seed) takes precedence over this.
+    pub fn with_rng_seed(&mut self, seed: u64) -> &mut Self {
+        self.seed = Some(RNG::seed_from_u64(seed).r#gen());
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`PoksError::InsufficientPlayers`] if there are less than two seats, and
+    /// [`PoksError::ConfigError`] if a seat that is no rebuy placeholder has no currency.
     pub fn build(self) -> Result<Lobby> {
+        let seed = self.seed;
+        self.build_inner(seed)
+    }
+
+    /// Build a lobby whose games are all derived from the master `seed`
+    ///
+    /// Every game gets its own seed, but two lobbies with the same master seed and players play
+    /// the same sequence of games.
+    pub fn build_with_seed(self, seed: Seed) -> Result<Lobby> {
+        self.build_inner(Some(seed))
+    }
+
+    fn build_inner(self, seed: Option<Seed>) -> Result<Lobby> {
         trace!("Building Lobby");
+        if self.players.len() < 2 {
+            return Err(PoksError::InsufficientPlayers {
+                count: self.players.len(),
+            });
+        }
+        for (pid, seat) in self.players.iter().enumerate() {
+            if seat.currency() == Currency::ZERO && !self.rebuy_placeholders.contains(&pid) {
+                match self.starting_stack {
+                    Some(stack) => seat.set_currency(stack),
+                    None => {
+                        return Err(PoksError::ConfigError {
+                            field: "starting currency".to_string(),
+                            reason: format!("player {pid} starts without currency"),
+                        });
+                    }
+                }
+            }
+        }
+        trace!("Dealing the first game");
+        check_session_over(&self.players)?;
+        let dealer = next_dealer(&self.players, 0)?;
+        let (small_blind, big_blind) = self.blinds.blinds_for(1);
+        let game = Game::build_with_blinds(
+            &self.players,
+            dealer,
+            self.deck_kind,
+            seed.map_or_else(Game::seed, |master| game_seed(master, 1)),
+            small_blind,
+            big_blind,
+        )?;
         let mut w = Lobby {
-            game: Game::build(&self.players, 0).unwrap(), // dummy
+            game,
             players: self.players,
             action_log: CircularQueue::with_capacity(ACTION_LOG_SIZE),
-            games_played: 0,
+            games_played: 1,
+            dealer,
+            missed_blinds: Vec::new(),
+            deck_kind: self.deck_kind,
+            blinds: self.blinds,
+            rake: self.rake,
+            total_rake: Currency::ZERO,
+            limit: self.limit,
+            seed,
+            local_players: self.local_players,
+            hand_finished_hook: None,
+            stats: Vec::new(),
+            hand_span: Span::none(),
+            turn_deadline: self.turn_deadline,
+            turn_started: None,
         };
-        trace!("Starting first game");
-        w.start_new_game()?;
+        w.prepare_game(None)?;
         for player in &w.players {
             assert!(player.behavior().hand().is_some())
         }
@@ -59,33 +255,297 @@ impl Lobby {
         LobbyBuilder::default()
     }
 
+    /// Deal the next game, the button moves on to the next seat that is still in the game
+    ///
+    /// If the current game is not finished yet, it is abandoned and every player gets back what
+    /// they bet in it.
     pub fn start_new_game(&mut self) -> Result<()> {
+        let seed = self
+            .seed
+            .map(|master| game_seed(master, self.games_played + 1));
+        self.start_new_game_inner(seed)
+    }
+
+    /// Everything the house raked from the pots of this lobby, including the current game
+    #[must_use]
+    pub fn total_rake(&self) -> Currency {
+        self.total_rake + self.game.rake()
+    }
+
+    /// All chips in play: the currency of every seat and the pot of the current game
+    ///
+    /// Once a game is finished, its pot was paid out to the seats and is not counted again.
+    /// Unless the currency of a seat is changed from the outside, only the rake takes chips out
+    /// of play, so this plus the [`total_rake`](Self::total_rake) stays the same.
+    #[must_use]
+    pub fn total_chips(&self) -> Currency {
+        let seats: Currency = self.players.iter().map(Seat::currency).sum();
+        if self.game.is_finished() {
+            seats
+        } else {
+            seats + self.game.pot()
+        }
+    }
+
+    /// The master seed all games of this lobby are derived from
+    ///
+    /// Write it down with [`Game::seed_to_hex`] for bug reports, the session can be replayed by
+    /// reading it back with [`Game::from_seed_str`] and building a lobby with the same players
+    /// with [`LobbyBuilder::build_with_seed`]. Lobbies without a master seed return [`None`],
+    /// their games are dealt from fresh seeds, see [`Game::current_seed`].
+    #[must_use]
+    pub fn seed(&self) -> Option<Seed> {
+        self.seed
+    }
+
+    /// Record the current game, so it can be played again
+    ///
+    /// See [`Game::record_replay`].
+    #[must_use]
+    pub fn record_replay(&self) -> Replay {
+        self.game.record_replay()
+    }
+
+    /// Start a new game that is dealt from the given `seed`
+    pub fn start_new_game_with_seed(&mut self, seed: Seed) -> Result<()> {
+        self.start_new_game_inner(Some(seed))
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`PoksError::SessionOver`] if only one seat has currency left, no new game is
+    /// started then. An unfinished game is refunded either way.
+    fn start_new_game_inner(&mut self, seed: Option<Seed>) -> Result<()> {
         trace!("Lobby starts a new game");
+        if !self.game.is_finished() {
+            self.game.refund_bets()?;
+        }
+        check_session_over(&self.players)?;
         self.games_played += 1;
 
-        let dealer_pos = self.games_played as PlayerID % self.players.len();
-        let game = Game::build(&self.players, dealer_pos)?;
+        let dealer_pos = next_dealer(&self.players, self.dealer)?;
+        let previous_bb = self.game.big_blind_position();
+        let (small_blind, big_blind) = self.blinds.blinds_for(self.games_played);
+        let game = Game::build_with_blinds(
+            &self.players,
+            dealer_pos,
+            self.deck_kind,
+            seed.unwrap_or_else(Game::seed),
+            small_blind,
+            big_blind,
+        )?;
+        // the previous game is over, its rake goes into the total
+        self.total_rake += self.game.rake();
         self.game = game;
+        self.dealer = dealer_pos;
+        self.prepare_game(Some(previous_bb))
+    }
+
+    /// Apply the rules of the lobby to the game that was just dealt and tell the players
+    ///
+    /// The seats the big blind skipped since `previous_bb` owe it, see
+    /// [`collect_missed_blinds`](Self::collect_missed_blinds). The first game has no previous
+    /// big blind.
+    fn prepare_game(&mut self, previous_bb: Option<PlayerID>) -> Result<()> {
+        self.hand_span = info_span!(
+            "hand",
+            game = self.games_played,
+            seed = %Game::seed_to_hex(&self.game.current_seed())
+        );
+        let _hand = self.hand_span.clone().entered();
+        debug!(dealer = self.dealer, "hand started");
+        self.game.set_rake(self.rake);
+        self.game.set_betting_limit(self.limit)?;
+        if let Some(previous_bb) = previous_bb {
+            self.collect_missed_blinds(previous_bb)?;
+        }
+        for (pid, seat) in self.players.iter().enumerate() {
+            seat.behavior_mut().on_hand_start(&self.game, pid);
+        }
         trace!("New game is ready");
         Ok(())
     }
 
-    pub fn tick_game(&mut self) -> Result<()> {
+    /// Remember the seats the big blind skipped since `previous_bb`, and let the seats that are
+    /// back in the game pay the big blind they missed
+    fn collect_missed_blinds(&mut self, previous_bb: PlayerID) -> Result<()> {
+        let n = self.players.len();
+        let bb = self.game.big_blind_position();
+        let (returned, still_out): (Vec<PlayerID>, Vec<PlayerID>) =
+            std::mem::take(&mut self.missed_blinds)
+                .into_iter()
+                .partition(|pid| self.game.players()[*pid].state() != PlayerState::Lost);
+        self.missed_blinds = still_out;
+        for pid in returned {
+            // the big blind pays its blind anyway
+            if pid != bb {
+                self.game.post_missed_blind(pid)?;
+            }
+        }
+
+        if bb == previous_bb {
+            return Ok(());
+        }
+        let skipped = (1..n)
+            .map(|offset| (previous_bb + offset) % n)
+            .take_while(|pid| *pid != bb)
+            .filter(|pid| self.game.players()[*pid].state() == PlayerState::Lost);
+        for pid in skipped.collect::<Vec<_>>() {
+            if !self.missed_blinds.contains(&pid) {
+                self.missed_blinds.push(pid);
+            }
+        }
+        Ok(())
+    }
+
+    /// Let the player whose turn it is act and process that action
+    ///
+    /// The game log is always moved into the action log afterwards, even if acting failed. When
+    /// the hand is finished by this tick, the last entry of the action log is the announcement
+    /// of the [`Winner`](crate::game::Winner).
+    pub fn tick_game(&mut self) -> Result<ActionOutcome> {
         if self.game.is_finished() {
             return Err(PoksError::GameFinished);
         }
         debug_assert!(self.game.turn() < self.players.len());
+        let _hand = self.hand_span.clone().entered();
+        #[cfg(debug_assertions)]
+        let chips_before = self.total_chips() + self.total_rake();
         let pid = self.game.turn();
-        let player = &mut self.players[pid];
-        let action = player.behavior_mut().act(&self.game)?;
-        let res = match self.game.process_action(action) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(e),
+        let action = self.players[pid].behavior_mut().act(&self.game);
+        let res = match action {
+            Ok(None) if self.turn_timed_out() => {
+                warn!(player = pid, "{}", PoksError::PlayerTimeout);
+                self.game.act_on_timeout()
+            }
+            action => action.and_then(|action| self.game.process_action(action)),
         };
+        #[cfg(debug_assertions)]
+        debug_assert_eq!(
+            self.total_chips() + self.total_rake(),
+            chips_before,
+            "chips were created or lost"
+        );
         self.update_action_log();
+        let finished = self.game.is_finished();
+        if finished {
+            self.record_stats();
+            self.report_hand_results();
+        }
+        if let Some(hook) = self.hand_finished_hook.as_mut().filter(|_| finished) {
+            hook(&self.game);
+        }
         res
     }
 
+    /// Whether the player whose turn it is has been undecided for longer than the turn deadline
+    ///
+    /// The clock starts the first time this is asked for a turn.
+    fn turn_timed_out(&mut self) -> bool {
+        let Some(deadline) = self.turn_deadline else {
+            return false;
+        };
+        let pid = self.game.turn();
+        if self.game.players()[pid].state() != PlayerState::Playing {
+            return false;
+        }
+        let turn = (self.games_played, pid, self.game.action_history().len());
+        match self.turn_started {
+            Some((started, since)) if started == turn => since.elapsed() >= deadline,
+            _ => {
+                self.turn_started = Some((turn, Instant::now()));
+                false
+            }
+        }
+    }
+
+    /// Give players `deadline` to decide when it is their turn, [`None`] lets them take as long
+    /// as they want
+    ///
+    /// A player who has not decided once the deadline passed checks if they can, and folds
+    /// otherwise, see [`Game::act_on_timeout`].
+    pub fn set_turn_deadline(&mut self, deadline: Option<Duration>) {
+        self.turn_deadline = deadline;
+    }
+
+    #[must_use]
+    pub fn turn_deadline(&self) -> Option<Duration> {
+        self.turn_deadline
+    }
+
+    /// Tick the game until a player has to decide, the hand is finished, or `max_steps` ticks
+    /// were made
+    ///
+    /// Computer players act right away, so this returns
+    /// [`ActionOutcome::AwaitingAction`] once it is the turn of a local player who has not set
+    /// an action yet, and [`ActionOutcome::Finished`] once the hand is over. Otherwise, the
+    /// outcome of the last tick is returned.
+    pub fn fast_forward(&mut self, max_steps: usize) -> Result<ActionOutcome> {
+        let mut outcome = ActionOutcome::AwaitingAction;
+        for _ in 0..max_steps {
+            if self.game.is_finished() {
+                return Ok(ActionOutcome::Finished);
+            }
+            outcome = self.tick_game()?;
+            if matches!(
+                outcome,
+                ActionOutcome::AwaitingAction | ActionOutcome::Finished
+            ) {
+                break;
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// Run `hook` every time a hand is finished by [`Lobby::tick_game`]
+    ///
+    /// Replaces the hook that was set before.
+    pub fn set_hand_finished_hook(&mut self, hook: HandFinishedHook) {
+        self.hand_finished_hook = Some(hook);
+    }
+
+    /// What the seat `pid` did in all hands of this lobby that were finished with
+    /// [`Lobby::tick_game`], [`None`] if there is no seat `pid`
+    #[must_use]
+    pub fn stats(&self, pid: PlayerID) -> Option<PlayerStats> {
+        if pid >= self.players.len() {
+            return None;
+        }
+        Some(self.stats.get(pid).copied().unwrap_or_default())
+    }
+
+    fn record_stats(&mut self) {
+        self.stats
+            .resize(self.players.len(), PlayerStats::default());
+        for (pid, stats) in self.stats.iter_mut().enumerate() {
+            stats.record_hand(&self.game, pid);
+        }
+    }
+
+    /// Tell every seat that was dealt into the finished game how it went for them
+    fn report_hand_results(&self) {
+        for (pid, seat) in self.players.iter().enumerate() {
+            if self.game.players()[pid].state() == PlayerState::Lost {
+                continue;
+            }
+            let bet = self.game.contributed_by(pid);
+            let won: Currency = self
+                .game
+                .winners()
+                .iter()
+                .flat_map(|w| w.shares())
+                .filter(|(winner, _)| *winner == pid)
+                .map(|(_, amount)| amount)
+                .sum();
+            let (ahead, net) = if won > bet {
+                (true, won - bet)
+            } else {
+                (false, bet - won)
+            };
+            seat.behavior_mut().on_hand_result(&self.game, ahead, net);
+        }
+    }
+
     fn update_action_log(&mut self) {
         let glog = self.game.take_gamelog();
         for i in glog.into_iter() {
@@ -97,7 +557,840 @@ impl Lobby {
         &self.action_log
     }
 
+    /// How many games were started in this lobby, including the current one
+    pub fn games_played(&self) -> u64 {
+        self.games_played
+    }
+
     pub fn players(&self) -> &[Seat] {
         &self.players
     }
+
+    /// The seats of the local players and the accessors to set their actions
+    pub fn local_players(&self) -> &[(PlayerID, ActionAccessor)] {
+        &self.local_players
+    }
+
+    /// The public information about every seat in the current game, in seat order
+    #[must_use]
+    pub fn seat_summaries(&self) -> Vec<SeatSummary> {
+        let game = &self.game;
+        self.players
+            .iter()
+            .zip(game.players())
+            .enumerate()
+            .map(|(pid, (seat, player))| SeatSummary {
+                name: seat.name().map(str::to_string),
+                currency: seat.currency(),
+                total_bet: player.total_bet(),
+                state: player.state(),
+                is_dealer: pid == game.dealer_position(),
+                is_sb: pid == game.small_blind_position(),
+                is_bb: pid == game.big_blind_position(),
+                is_current_turn: pid == game.turn() && !game.is_finished(),
+            })
+            .collect()
+    }
+
+    /// How many seats have currency left to play with
+    ///
+    /// Seats without currency are dealt into new games as
+    /// [`PlayerState::Lost`](crate::players::PlayerState::Lost).
+    pub fn remaining_players(&self) -> usize {
+        self.players
+            .iter()
+            .filter(|seat| seat.currency() > Currency::ZERO)
+            .count()
+    }
+
+    /// Iterate over the seats that are still in the current hand
+    ///
+    /// Seats that folded or lost are left out.
+    pub fn active_seats(&self) -> impl Iterator<Item = (PlayerID, &Seat)> {
+        self.players
+            .iter()
+            .enumerate()
+            .filter(|(pid, _)| self.game.players()[*pid].state().is_playing())
+    }
+}
+
+impl Debug for Lobby {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Lobby")
+            .field("players", &self.players)
+            .field("game", &self.game)
+            .field("action_log", &self.action_log)
+            .field("games_played", &self.games_played)
+            .field("dealer", &self.dealer)
+            .field("missed_blinds", &self.missed_blinds)
+            .field("deck_kind", &self.deck_kind)
+            .field("blinds", &self.blinds)
+            .field("rake", &self.rake)
+            .field("total_rake", &self.total_rake)
+            .field("limit", &self.limit)
+            .field("seed", &self.seed)
+            .field("local_players", &self.local_players)
+            .field("stats", &self.stats)
+            .field("turn_deadline", &self.turn_deadline)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Mix the number of a game into the master seed of a lobby
+fn game_seed(master: Seed, game: u64) -> Seed {
+    let mut seed = master;
+    for (byte, game) in seed.iter_mut().zip(game.to_le_bytes()) {
+        *byte ^= game;
+    }
+    seed
+}
+
+/// The next seat after `dealer` that is still in the game
+fn next_dealer(players: &[Seat], dealer: PlayerID) -> Result<PlayerID> {
+    let n = players.len();
+    (1..=n)
+        .map(|offset| (dealer + offset) % n)
+        .find(|pid| players[*pid].currency() > Currency::ZERO)
+        .ok_or(PoksError::NoActivePlayers)
+}
+
+/// Fail with [`PoksError::SessionOver`] if only one seat has currency left
+fn check_session_over(players: &[Seat]) -> Result<()> {
+    let mut remaining = players
+        .iter()
+        .enumerate()
+        .filter(|(_, seat)| seat.currency() > Currency::ZERO);
+    match (remaining.next(), remaining.next()) {
+        (Some((winner, _)), None) => Err(PoksError::SessionOver { winner }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+
+    use crate::currency::Currency;
+    use crate::errors::PoksError;
+    use crate::game::{Action, ActionOutcome, DeckKind, Game, PlayerID, Seed};
+    use crate::lobby::{BlindSchedule, Lobby, RakeConfig};
+    use crate::players::local::ActionAccessor;
+    use crate::players::{PlayerBasicFields, PlayerCPU, PlayerLocal, PlayerState, Strategy};
+    use crate::{CU, Result, player_impl};
+
+    #[test]
+    fn test_cpu_named() {
+        let mut builder = Lobby::builder();
+        builder
+            .cpu_named("Rocky", CU!(5000), Strategy::TightPassive)
+            .unwrap()
+            .cpu_named("Maverick", CU!(5000), Strategy::LooseAggressive)
+            .unwrap()
+            .cpu_named("Dice", CU!(5000), Strategy::Random)
+            .unwrap();
+        let lobby = builder.build().unwrap();
+
+        let names: Vec<_> = lobby.players().iter().map(|s| s.name()).collect();
+        assert_eq!(names, [Some("Rocky"), Some("Maverick"), Some("Dice")]);
+        let strategies: Vec<_> = lobby
+            .players()
+            .iter()
+            .map(|s| s.behavior().strategy())
+            .collect();
+        assert_eq!(
+            strategies,
+            [
+                Some(Strategy::TightPassive),
+                Some(Strategy::LooseAggressive),
+                Some(Strategy::Random)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_cpu_players() {
+        let mut builder = Lobby::builder();
+        builder.add_cpu_players(6, CU!(1234)).unwrap();
+        let lobby = builder.build().unwrap();
+        assert_eq!(lobby.players().len(), 6);
+        assert_eq!(lobby.total_chips(), CU!(1234) * 6);
+        // the blinds are already posted
+        for (pid, seat) in lobby.players().iter().enumerate() {
+            let posted = lobby.game.players()[pid].total_bet();
+            assert_eq!(seat.currency() + posted, CU!(1234));
+        }
+
+        let mut builder = Lobby::builder();
+        builder
+            .add_player(Box::new(PlayerCPU::default()))
+            .unwrap()
+            .add_player(Box::new(PlayerCPU::default()))
+            .unwrap()
+            .with_starting_stack(CU!(100));
+        let lobby = builder.build().unwrap();
+        assert_eq!(lobby.total_chips(), CU!(200));
+
+        let mut builder = Lobby::builder();
+        builder.add_cpu_players(1, CU!(100)).unwrap();
+        assert!(matches!(
+            builder.build(),
+            Err(PoksError::InsufficientPlayers { count: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_turn_deadline_folds() {
+        let mut builder = Lobby::builder();
+        builder
+            .add_local_player()
+            .unwrap()
+            .add_local_player()
+            .unwrap()
+            .with_starting_stack(CU!(100))
+            .turn_deadline(Duration::from_millis(20));
+        let mut lobby = builder.build().unwrap();
+        let pid = lobby.game.turn();
+
+        // nobody sets an action
+        assert_eq!(lobby.tick_game().unwrap(), ActionOutcome::AwaitingAction);
+        assert_eq!(lobby.tick_game().unwrap(), ActionOutcome::AwaitingAction);
+        std::thread::sleep(Duration::from_millis(30));
+        // the small blind still owes half the big blind and can not check
+        assert_eq!(lobby.tick_game().unwrap(), ActionOutcome::Finished);
+        assert_eq!(lobby.game.action_history(), [(pid, Action::Fold)]);
+        assert!(
+            lobby
+                .action_log()
+                .iter()
+                .any(|(p, line)| *p == Some(pid) && line.contains("timeout"))
+        );
+    }
+
+    #[test]
+    fn test_active_seats() {
+        let mut builder = Lobby::builder();
+        let mut accessors = Vec::new();
+        for _ in 0..3 {
+            let player = Box::new(PlayerLocal::new());
+            accessors.push(player.action_field_reference());
+            builder.add_player(player).unwrap();
+        }
+        for seat in builder.players.iter_mut() {
+            seat.set_currency(CU!(5000));
+        }
+        let mut lobby = builder.build().unwrap();
+        assert_eq!(lobby.active_seats().count(), 3);
+
+        // bust the seat that acts after the current one
+        let busted = (lobby.game.turn() + 1) % 3;
+        lobby.players()[busted].set_currency(CU!(0));
+        PlayerLocal::set_action(&accessors[lobby.game.turn()], lobby.game.action_call());
+        lobby.tick_game().unwrap();
+        assert_eq!(lobby.game.turn(), busted);
+        lobby.tick_game().unwrap();
+
+        let active: Vec<PlayerID> = lobby.active_seats().map(|(pid, _)| pid).collect();
+        assert_eq!(active.len(), 2);
+        assert!(!active.contains(&busted));
+    }
+
+    #[test]
+    fn test_hand_finished_hook() {
+        let mut builder = Lobby::builder();
+        for name in ["A", "B", "C"] {
+            builder
+                .cpu_named(name, CU!(5000), Strategy::Random)
+                .unwrap();
+        }
+        let mut lobby = builder.build().unwrap();
+        let finished = Rc::new(Cell::new(0));
+        let counter = finished.clone();
+        lobby.set_hand_finished_hook(Box::new(move |game| {
+            assert!(game.is_finished());
+            counter.set(counter.get() + 1);
+        }));
+
+        for hand in 1..=3 {
+            while !lobby.game.is_finished() {
+                lobby.tick_game().unwrap();
+            }
+            assert!(lobby.tick_game().is_err());
+            assert_eq!(finished.get(), hand);
+            lobby.start_new_game().unwrap();
+        }
+    }
+
+    fn seeded_local_lobby(seed: Seed) -> (Lobby, Vec<ActionAccessor>) {
+        let mut builder = Lobby::builder();
+        let mut accessors = Vec::new();
+        for _ in 0..3 {
+            let player = Box::new(PlayerLocal::new());
+            accessors.push(player.action_field_reference());
+            builder.add_player(player).unwrap();
+        }
+        for seat in builder.players.iter_mut() {
+            seat.set_currency(CU!(5000));
+        }
+        (builder.build_with_seed(seed).unwrap(), accessors)
+    }
+
+    #[test]
+    fn test_lobby_build_with_seed() {
+        let mut logs = Vec::new();
+        for _ in 0..2 {
+            let (mut lobby, accessors) = seeded_local_lobby([42; 32]);
+            for _ in 0..10 {
+                while !lobby.game.is_finished() {
+                    let call = lobby.game.action_call();
+                    PlayerLocal::set_action(&accessors[lobby.game.turn()], call);
+                    lobby.tick_game().unwrap();
+                }
+                lobby.start_new_game().unwrap();
+            }
+            let log: Vec<_> = lobby.action_log().iter().cloned().collect();
+            logs.push(log);
+        }
+        assert_eq!(logs[0], logs[1]);
+
+        let (other, _) = seeded_local_lobby([43; 32]);
+        let (lobby, _) = seeded_local_lobby([42; 32]);
+        assert_ne!(lobby.game.deck(), other.game.deck());
+    }
+
+    #[test]
+    fn test_seeded_cpu_decisions() {
+        let mut logs = Vec::new();
+        for _ in 0..2 {
+            let mut builder = Lobby::builder();
+            for (name, strategy) in [
+                ("A", Strategy::Random),
+                ("B", Strategy::LooseAggressive),
+                ("C", Strategy::TightPassive),
+            ] {
+                builder.cpu_named(name, CU!(5000), strategy).unwrap();
+            }
+            let mut lobby = builder.build_with_seed([17; 32]).unwrap();
+            for _ in 0..10 {
+                while !lobby.game.is_finished() {
+                    lobby.tick_game().unwrap();
+                }
+                lobby.start_new_game().unwrap();
+            }
+            let log: Vec<_> = lobby.action_log().iter().cloned().collect();
+            logs.push(log);
+        }
+        assert_eq!(logs[0], logs[1]);
+    }
+
+    #[test]
+    fn test_with_rng_seed() {
+        let session = |seed: u64| {
+            let mut builder = Lobby::builder();
+            for (name, strategy) in [
+                ("A", Strategy::Random),
+                ("B", Strategy::LooseAggressive),
+                ("C", Strategy::TightPassive),
+                ("D", Strategy::Random),
+            ] {
+                builder.cpu_named(name, CU!(5000), strategy).unwrap();
+            }
+            builder.with_rng_seed(seed);
+            let mut lobby = builder.build().unwrap();
+            for _ in 0..20 {
+                while !lobby.game.is_finished() {
+                    lobby.tick_game().unwrap();
+                }
+                lobby.start_new_game().unwrap();
+            }
+            let stacks: Vec<Currency> = lobby.players().iter().map(|s| s.currency()).collect();
+            let log: Vec<_> = lobby.action_log().iter().cloned().collect();
+            (lobby.seed(), stacks, log)
+        };
+        let (seed, stacks, log) = session(1337);
+        assert!(seed.is_some());
+        assert_eq!(session(1337), (seed, stacks, log));
+        assert_ne!(session(1338).0, seed);
+    }
+
+    #[test]
+    fn test_rake() {
+        let mut builder = Lobby::builder();
+        builder
+            .add_local_player()
+            .unwrap()
+            .add_local_player()
+            .unwrap();
+        for seat in builder.players.iter_mut() {
+            seat.set_currency(CU!(5000));
+        }
+        builder.rake(RakeConfig::new(5, CU!(3)).unwrap());
+        let mut lobby = builder.build().unwrap();
+        let accessors: Vec<ActionAccessor> = lobby
+            .local_players()
+            .iter()
+            .map(|(_, a)| a.clone())
+            .collect();
+        let act = |lobby: &mut Lobby, action: Action| {
+            PlayerLocal::set_action(&accessors[lobby.game.turn()], action);
+            lobby.tick_game().unwrap();
+        };
+
+        // no flop, no drop
+        act(&mut lobby, Action::Fold);
+        assert!(lobby.game.is_finished());
+        assert_eq!(lobby.total_rake(), CU!(0));
+
+        lobby.start_new_game().unwrap();
+        act(&mut lobby, Action::Raise(CU!(50)));
+        let call = lobby.game.action_call();
+        act(&mut lobby, call);
+        assert_eq!(lobby.game.pot(), CU!(100));
+        let winner = 1 - lobby.game.turn();
+        let before = lobby.players()[winner].currency();
+        act(&mut lobby, Action::Fold);
+        assert_eq!(lobby.game.winner().unwrap().amount(), CU!(97));
+        assert_eq!(lobby.players()[winner].currency(), before + CU!(97));
+        assert_eq!(lobby.total_rake(), CU!(3));
+
+        lobby.start_new_game().unwrap();
+        assert_eq!(lobby.total_rake(), CU!(3));
+    }
+
+    #[test]
+    fn test_total_chips() {
+        let mut builder = Lobby::builder();
+        for (name, strategy) in [
+            ("A", Strategy::Random),
+            ("B", Strategy::LooseAggressive),
+            ("C", Strategy::TightPassive),
+            ("D", Strategy::LooseAggressive),
+        ] {
+            builder.cpu_named(name, CU!(500), strategy).unwrap();
+        }
+        let mut lobby = builder.build().unwrap();
+        // the blinds are already in the pot
+        assert_eq!(lobby.total_chips(), CU!(2000));
+        // an abandoned game gives the bets back
+        lobby.start_new_game().unwrap();
+        assert_eq!(lobby.total_chips(), CU!(2000));
+
+        for _ in 0..20 {
+            while !lobby.game.is_finished() {
+                lobby.tick_game().unwrap();
+                assert_eq!(lobby.total_chips(), CU!(2000));
+            }
+            let stacks: Currency = lobby.players().iter().map(|s| s.currency()).sum();
+            assert_eq!(stacks, CU!(2000));
+            match lobby.start_new_game() {
+                Err(PoksError::SessionOver { .. }) => break,
+                res => res.unwrap(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_lobby_too_many_players() {
+        let mut builder = Lobby::builder();
+        for i in 0..30 {
+            builder
+                .cpu_named(format!("CPU {i}"), CU!(5000), Strategy::Random)
+                .unwrap();
+        }
+        assert!(matches!(
+            builder.build(),
+            Err(PoksError::TooManyPlayers { requested: 30, .. })
+        ));
+    }
+
+    #[test]
+    fn test_seat_summaries() {
+        let mut builder = Lobby::builder();
+        for name in ["A", "B", "C"] {
+            builder
+                .cpu_named(name, CU!(5000), Strategy::Random)
+                .unwrap();
+        }
+        let lobby = builder.build().unwrap();
+        let summaries = lobby.seat_summaries();
+        assert_eq!(summaries.len(), 3);
+        assert_eq!(summaries.iter().filter(|s| s.is_dealer).count(), 1);
+        assert_eq!(summaries.iter().filter(|s| s.is_sb).count(), 1);
+        assert_eq!(summaries.iter().filter(|s| s.is_bb).count(), 1);
+        assert!(summaries.iter().all(|s| {
+            [s.is_dealer, s.is_sb, s.is_bb]
+                .iter()
+                .filter(|b| **b)
+                .count()
+                == 1
+        }));
+
+        let turn = lobby.game.turn();
+        assert!(summaries[turn].is_current_turn);
+        assert_eq!(summaries.iter().filter(|s| s.is_current_turn).count(), 1);
+        let bb = summaries.iter().find(|s| s.is_bb).unwrap();
+        assert_eq!(bb.total_bet, lobby.game.big_blind());
+        assert_eq!(summaries[0].name.as_deref(), Some("A"));
+    }
+
+    #[test]
+    fn test_local_players() {
+        let mut builder = Lobby::builder();
+        builder
+            .add_local_player()
+            .unwrap()
+            .cpu_named("CPU", CU!(5000), Strategy::Random)
+            .unwrap()
+            .add_local_player()
+            .unwrap();
+        for seat in builder.players.iter_mut() {
+            seat.set_currency(CU!(5000));
+        }
+        let lobby = builder.build().unwrap();
+
+        let locals = lobby.local_players();
+        assert_eq!(locals.len(), 2);
+        assert_eq!(locals[0].0, 0);
+        assert_eq!(locals[1].0, 2);
+        assert!(!Arc::ptr_eq(&locals[0].1, &locals[1].1));
+
+        // the accessor controls the player in its seat
+        PlayerLocal::set_action(&locals[1].1, Action::Fold);
+        assert_eq!(
+            lobby.players()[2].behavior_mut().act(&lobby.game).unwrap(),
+            Some(Action::Fold)
+        );
+        assert_eq!(
+            lobby.players()[0].behavior_mut().act(&lobby.game).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_zero_starting_currency() {
+        let mut builder = Lobby::builder();
+        builder
+            .cpu_named("A", CU!(5000), Strategy::Random)
+            .unwrap()
+            .cpu_named("B", CU!(0), Strategy::Random)
+            .unwrap();
+        assert!(matches!(
+            builder.build(),
+            Err(PoksError::ConfigError { .. })
+        ));
+
+        let mut builder = Lobby::builder();
+        builder
+            .cpu_named("A", CU!(5000), Strategy::Random)
+            .unwrap()
+            .cpu_named("B", CU!(5000), Strategy::Random)
+            .unwrap()
+            .add_rebuy_placeholder(Box::new(PlayerCPU::default()))
+            .unwrap();
+        let lobby = builder.build().unwrap();
+        assert_eq!(lobby.game.players()[2].state(), PlayerState::Lost);
+    }
+
+    /// Fold the current game around to the big blind
+    ///
+    /// Seats that bust after this stay out, an unfinished game would give them back their bets.
+    fn fold_hand(lobby: &mut Lobby) {
+        while !lobby.game.is_finished() {
+            lobby.game.process_action(Some(Action::Fold)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_session_over() {
+        let mut builder = Lobby::builder();
+        for name in ["A", "B", "C"] {
+            builder
+                .cpu_named(name, CU!(5000), Strategy::Random)
+                .unwrap();
+        }
+        let mut lobby = builder.build().unwrap();
+        assert_eq!(lobby.remaining_players(), 3);
+
+        fold_hand(&mut lobby);
+        lobby.players()[0].set_currency(CU!(0));
+        lobby.start_new_game().unwrap();
+        assert_eq!(lobby.remaining_players(), 2);
+        assert_eq!(lobby.game.players()[0].state(), PlayerState::Lost);
+
+        fold_hand(&mut lobby);
+        lobby.players()[2].set_currency(CU!(0));
+        assert_eq!(lobby.remaining_players(), 1);
+        assert!(matches!(
+            lobby.start_new_game(),
+            Err(PoksError::SessionOver { winner: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_blind_schedule_levels() {
+        let mut builder = Lobby::builder();
+        for name in ["A", "B", "C"] {
+            builder
+                .cpu_named(name, CU!(5000), Strategy::Random)
+                .unwrap();
+        }
+        let schedule =
+            BlindSchedule::new(vec![(1, CU!(0, 50), CU!(1)), (3, CU!(5), CU!(10))]).unwrap();
+        builder.blinds(schedule);
+        let mut lobby = builder.build().unwrap();
+        assert_eq!(lobby.game.big_blind(), CU!(1));
+
+        lobby.start_new_game().unwrap();
+        assert_eq!(lobby.game.big_blind(), CU!(1));
+
+        // the third game reaches the next level
+        lobby.start_new_game().unwrap();
+        let game = &lobby.game;
+        assert_eq!(game.small_blind(), CU!(5));
+        assert_eq!(game.big_blind(), CU!(10));
+        assert_eq!(
+            game.players()[game.small_blind_position()].round_bet(),
+            CU!(5)
+        );
+        assert_eq!(
+            game.players()[game.big_blind_position()].round_bet(),
+            CU!(10)
+        );
+    }
+
+    #[derive(Debug, Default)]
+    struct HandCounter {
+        base: PlayerBasicFields,
+        hands: Arc<AtomicU64>,
+    }
+
+    player_impl!(
+        HandCounter,
+        base,
+        fn act(&mut self, game: &Game) -> Result<Option<Action>> {
+            Ok(Some(game.action_call()))
+        },
+        fn on_hand_start(&mut self, game: &Game, my_id: PlayerID) {
+            assert!(game.players()[my_id].state().is_playing());
+            self.hands.fetch_add(1, Ordering::Relaxed);
+        }
+    );
+
+    /// Whether each finished hand was won, and the net, as reported to the seat
+    type HandResults = Arc<std::sync::Mutex<Vec<(bool, Currency)>>>;
+
+    #[derive(Debug, Default)]
+    struct ResultRecorder {
+        base: PlayerBasicFields,
+        results: HandResults,
+    }
+
+    player_impl!(
+        ResultRecorder,
+        base,
+        fn act(&mut self, game: &Game) -> Result<Option<Action>> {
+            Ok(Some(game.action_call()))
+        },
+        fn on_hand_result(&mut self, game: &Game, won: bool, net: Currency) {
+            assert!(game.is_finished());
+            self.results.lock().unwrap().push((won, net));
+        }
+    );
+
+    #[test]
+    fn test_on_hand_result() {
+        let mut builder = Lobby::builder();
+        let mut results: Vec<HandResults> = Vec::new();
+        for _ in 0..3 {
+            let player = ResultRecorder::default();
+            results.push(player.results.clone());
+            builder.add_player(Box::new(player)).unwrap();
+        }
+        builder.with_starting_stack(CU!(5000));
+        let mut lobby = builder.build().unwrap();
+        for hand in 1..=3 {
+            let before: Vec<Currency> = lobby
+                .players()
+                .iter()
+                .enumerate()
+                .map(|(pid, seat)| seat.currency() + lobby.game.contributed_by(pid))
+                .collect();
+            while !lobby.game.is_finished() {
+                lobby.tick_game().unwrap();
+            }
+            for (pid, seat) in lobby.players().iter().enumerate() {
+                let results = results[pid].lock().unwrap();
+                assert_eq!(results.len(), hand);
+                let (won, net) = results[hand - 1];
+                if won {
+                    assert_eq!(seat.currency(), before[pid] + net);
+                } else {
+                    assert_eq!(seat.currency() + net, before[pid]);
+                }
+            }
+            lobby.start_new_game().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_on_hand_start() {
+        let mut builder = Lobby::builder();
+        let mut counters = Vec::new();
+        for _ in 0..3 {
+            let player = HandCounter::default();
+            counters.push(player.hands.clone());
+            builder.add_player(Box::new(player)).unwrap();
+        }
+        for seat in builder.players.iter_mut() {
+            seat.set_currency(CU!(5000));
+        }
+        let mut lobby = builder.build().unwrap();
+        for _ in 0..4 {
+            while !lobby.game.is_finished() {
+                lobby.tick_game().unwrap();
+            }
+            lobby.start_new_game().unwrap();
+        }
+
+        assert_eq!(lobby.games_played(), 5);
+        for counter in counters {
+            assert_eq!(counter.load(Ordering::Relaxed), lobby.games_played());
+        }
+    }
+
+    #[test]
+    fn test_fast_forward() {
+        let mut builder = Lobby::builder();
+        for name in ["A", "B", "C", "D"] {
+            builder
+                .cpu_named(name, CU!(5000), Strategy::Random)
+                .unwrap();
+        }
+        let mut lobby = builder.build().unwrap();
+        assert_eq!(lobby.fast_forward(1000).unwrap(), ActionOutcome::Finished);
+        assert!(lobby.game.is_finished());
+        assert_eq!(lobby.fast_forward(1000).unwrap(), ActionOutcome::Finished);
+
+        // a local player stops the fast forward on their turn
+        let mut builder = Lobby::builder();
+        builder
+            .cpu_named("A", CU!(5000), Strategy::Random)
+            .unwrap()
+            .add_local_player()
+            .unwrap()
+            .cpu_named("B", CU!(5000), Strategy::Random)
+            .unwrap();
+        builder.players[1].set_currency(CU!(5000));
+        let mut lobby = builder.build().unwrap();
+        // the local player is the dealer of the first game and opens the betting
+        assert_eq!(
+            lobby.fast_forward(1000).unwrap(),
+            ActionOutcome::AwaitingAction
+        );
+        assert_eq!(lobby.game.turn(), 1);
+    }
+
+    #[test]
+    fn test_short_deck_lobby() {
+        let mut builder = Lobby::builder();
+        builder
+            .deck(DeckKind::ShortDeck)
+            .cpu_named("A", CU!(5000), Strategy::Random)
+            .unwrap()
+            .cpu_named("B", CU!(5000), Strategy::Random)
+            .unwrap();
+        let lobby = builder.build().unwrap();
+
+        assert_eq!(lobby.game.deck_kind(), DeckKind::ShortDeck);
+        let dealt = 2 * lobby.players().len();
+        assert_eq!(lobby.game.deck().len() + dealt, 36);
+    }
+
+    #[test]
+    fn test_button_skips_eliminated_seat() {
+        let mut builder = Lobby::builder();
+        for name in ["A", "B", "C", "D"] {
+            builder.cpu_named(name, CU!(100), Strategy::Random).unwrap();
+        }
+        let mut lobby = builder.build().unwrap();
+        let positions = |lobby: &Lobby| {
+            let game = &lobby.game;
+            (
+                game.dealer_position(),
+                game.small_blind_position(),
+                game.big_blind_position(),
+            )
+        };
+        assert_eq!(positions(&lobby), (1, 2, 3));
+
+        // seat 2 busts, the button and the blinds pass it
+        fold_hand(&mut lobby);
+        lobby.players()[2].set_currency(CU!(0));
+        lobby.start_new_game().unwrap();
+        assert_eq!(positions(&lobby), (3, 0, 1));
+        lobby.start_new_game().unwrap();
+        assert_eq!(positions(&lobby), (0, 1, 3));
+        assert_eq!(lobby.game.players()[2].state(), PlayerState::Lost);
+        assert_eq!(lobby.game.pot(), CU!(1, 50));
+
+        // back in the game, seat 2 owes the big blind it missed on top of its small blind
+        lobby.players()[2].set_currency(CU!(100));
+        lobby.start_new_game().unwrap();
+        assert_eq!(positions(&lobby), (1, 2, 3));
+        assert_eq!(lobby.game.contributed_by(2), CU!(1, 50));
+        assert_eq!(lobby.game.players()[2].round_bet(), CU!(0, 50));
+        assert_eq!(lobby.players()[2].currency(), CU!(98, 50));
+
+        // the debt is paid, the button moves on to seat 2
+        lobby.start_new_game().unwrap();
+        assert_eq!(positions(&lobby), (2, 3, 0));
+        assert_eq!(lobby.game.contributed_by(2), CU!(0));
+    }
+
+    #[test]
+    fn test_heads_up_after_eliminations() {
+        let mut builder = Lobby::builder();
+        for name in ["A", "B", "C", "D"] {
+            builder
+                .cpu_named(name, CU!(5000), Strategy::Random)
+                .unwrap();
+        }
+        let mut lobby = builder.build().unwrap();
+        assert!(!lobby.game.heads_up_after_eliminations());
+
+        // eliminate two players, leaving seats 0 and 2
+        fold_hand(&mut lobby);
+        lobby.players()[1].set_currency(CU!(0));
+        lobby.players()[3].set_currency(CU!(0));
+        lobby.start_new_game().unwrap();
+
+        let game = &lobby.game;
+        assert!(game.heads_up_after_eliminations());
+        let dealer = game.dealer_position();
+        assert!(dealer == 0 || dealer == 2);
+        assert_eq!(game.small_blind_position(), dealer);
+        assert_eq!(game.big_blind_position(), 2 - dealer);
+        assert_eq!(game.players()[1].state(), PlayerState::Lost);
+        assert_eq!(game.players()[3].state(), PlayerState::Lost);
+    }
+
+    #[test]
+    #[tracing_test::traced_test]
+    fn test_hand_span_has_seed() {
+        let mut builder = Lobby::builder();
+        for name in ["A", "B", "C"] {
+            builder
+                .cpu_named(name, CU!(5000), Strategy::TightPassive)
+                .unwrap();
+        }
+        let mut lobby = builder.build_with_seed([7; 32]).unwrap();
+        lobby.tick_game().unwrap();
+
+        let seed = Game::seed_to_hex(&lobby.game.current_seed());
+        assert!(logs_contain(&format!("hand{{game=1 seed={seed}}}")));
+        assert!(logs_contain("action{player="));
+    }
 }
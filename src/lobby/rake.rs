@@ -0,0 +1,56 @@
+use crate::Result;
+use crate::currency::Currency;
+use crate::errors::PoksError;
+
+/// The commission the house takes from every pot
+///
+/// `percent` of each pot is taken, but no more than `cap` per hand. Hands that end before the
+/// flop are not raked ("no flop, no drop"), and neither are side pots that only one player can
+/// win, they hold the part of a bet nobody called.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RakeConfig {
+    pub percent: u8,
+    pub cap: Currency,
+}
+
+impl RakeConfig {
+    /// # Errors
+    ///
+    /// Returns [`PoksError::ConfigError`] if `percent` is 100 or more, the winners have to
+    /// get something.
+    pub fn new(percent: u8, cap: Currency) -> Result<Self> {
+        if percent >= 100 {
+            return Err(PoksError::ConfigError {
+                field: "rake".to_string(),
+                reason: format!("can not take {percent}% of a pot"),
+            });
+        }
+        Ok(Self { percent, cap })
+    }
+
+    /// The rake for a pot of `amount`, when `taken` was already raked in this hand
+    ///
+    /// Fractions of a cent are left in the pot.
+    #[must_use]
+    pub fn rake_for(&self, amount: Currency, taken: Currency) -> Currency {
+        let rake = (u128::from(*amount) * u128::from(self.percent.min(100)) / 100) as u64;
+        Currency::from(rake).min(self.cap.saturating_sub(taken))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CU;
+    use crate::lobby::RakeConfig;
+
+    #[test]
+    fn test_rake_for() {
+        let rake = RakeConfig::new(5, CU!(3)).unwrap();
+        assert_eq!(rake.rake_for(CU!(20), CU!(0)), CU!(1));
+        assert_eq!(rake.rake_for(CU!(100), CU!(0)), CU!(3));
+        assert_eq!(rake.rake_for(CU!(100), CU!(2)), CU!(1));
+        assert_eq!(rake.rake_for(CU!(0, 19), CU!(0)), CU!(0));
+        assert!(RakeConfig::new(100, CU!(3)).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use crate::errors::PoksError;
+use crate::game::{ActionOutcome, Winner};
+use crate::lobby::Lobby;
+use crate::{Result, err_int};
+
+/// Plays the games of a [`Lobby`] without a user interface
+///
+/// Useful for tests, simulations and benchmarks. Computer players act right away, local
+/// players are waited for: their actions have to be set from another thread.
+#[derive(Debug)]
+pub struct GameRunner {
+    lobby: Lobby,
+}
+
+impl GameRunner {
+    pub fn new(lobby: Lobby) -> Self {
+        Self { lobby }
+    }
+
+    pub fn lobby(&self) -> &Lobby {
+        &self.lobby
+    }
+
+    pub fn lobby_mut(&mut self) -> &mut Lobby {
+        &mut self.lobby
+    }
+
+    pub fn into_lobby(self) -> Lobby {
+        self.lobby
+    }
+
+    /// Play the current hand to the end and return the winner of its main pot
+    ///
+    /// If the current hand is already finished, a new one is started first. While a player has
+    /// not decided yet ([`ActionOutcome::AwaitingAction`]), this yields to other threads and
+    /// asks again, it does not sleep.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::SessionOver`] if a new hand was needed but only one seat has
+    /// currency left, and any error of a player acting or of the game processing an action.
+    pub fn run_hand(&mut self) -> Result<Winner> {
+        if self.lobby.game.is_finished() {
+            self.lobby.start_new_game()?;
+        }
+        loop {
+            match self.lobby.tick_game()? {
+                ActionOutcome::Finished => break,
+                ActionOutcome::AwaitingAction => std::thread::yield_now(),
+                ActionOutcome::Applied | ActionOutcome::Skipped => (),
+            }
+        }
+        self.lobby
+            .game
+            .winner()
+            .ok_or_else(|| err_int!("finished hand has no winner"))
+    }
+
+    /// Play up to `hands` hands and return the winners of their main pots
+    ///
+    /// The session ends early, without an error, once only one seat has currency left.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of [`GameRunner::run_hand`] other than
+    /// [`PoksError::SessionOver`].
+    pub fn run_session(&mut self, hands: usize) -> Result<Vec<Winner>> {
+        let mut winners = Vec::with_capacity(hands);
+        for _ in 0..hands {
+            match self.run_hand() {
+                Ok(winner) => winners.push(winner),
+                Err(PoksError::SessionOver { .. }) => break,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(winners)
+    }
+}
+
+impl From<Lobby> for GameRunner {
+    fn from(lobby: Lobby) -> Self {
+        Self::new(lobby)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::CU;
+    use crate::game::Action;
+    use crate::lobby::{GameRunner, Lobby};
+    use crate::players::{PlayerLocal, Strategy};
+
+    fn runner(players: usize, strategy: Strategy) -> GameRunner {
+        let mut builder = Lobby::builder();
+        for i in 0..players {
+            builder
+                .cpu_named(format!("CPU {i}"), CU!(5000), strategy)
+                .unwrap();
+        }
+        GameRunner::new(builder.build().unwrap())
+    }
+
+    #[test]
+    fn test_run_100_hands() {
+        // passive players only make the smallest raises, nobody busts in 100 hands
+        let mut runner = runner(6, Strategy::TightPassive);
+        let chips = runner.lobby().total_chips();
+        for _ in 0..100 {
+            let winner = runner.run_hand().unwrap();
+            assert!(runner.lobby().game.is_finished());
+            assert_eq!(runner.lobby().game.winner(), Some(winner));
+            assert_eq!(runner.lobby().total_chips(), chips);
+        }
+        // the first hand was dealt when the lobby was built
+        assert_eq!(runner.lobby().games_played(), 100);
+    }
+
+    #[test]
+    fn test_run_session() {
+        let mut runner = runner(3, Strategy::LooseAggressive);
+        let winners = runner.run_session(10).unwrap();
+        // the session may end early, when a player has all the chips
+        assert!(!winners.is_empty() && winners.len() <= 10);
+        assert_eq!(runner.lobby().games_played(), winners.len() as u64);
+    }
+
+    #[test]
+    fn test_run_hand_waits_for_local_player() {
+        let mut builder = Lobby::builder();
+        builder
+            .cpu_named("CPU", CU!(5000), Strategy::TightPassive)
+            .unwrap()
+            .add_local_player()
+            .unwrap();
+        builder.with_starting_stack(CU!(5000));
+        let mut runner = GameRunner::new(builder.build().unwrap());
+        let accessor = runner.lobby().local_players()[0].1.clone();
+
+        // the local player decides only after the runner started waiting for them
+        let input = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            PlayerLocal::set_action(&accessor, Action::Fold);
+        });
+        runner.run_hand().unwrap();
+        input.join().unwrap();
+        assert!(runner.lobby().game.is_finished());
+    }
+}
@@ -2,22 +2,91 @@ use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use tracing::trace;
 
-use crate::{currency::Currency, game::Cards, players::PlayerBehavior};
+use crate::{
+    Result,
+    currency::Currency,
+    errors::PoksError,
+    game::Cards,
+    players::{PlayerBehavior, PlayerState, ShowPreference},
+};
 
 pub type BehaveBox = Box<dyn PlayerBehavior + Send + Sync>;
 
 #[derive(Debug, Clone)]
 pub struct Seat {
     inner: Arc<RwLock<BehaveBox>>,
+    name: Option<String>,
+    show_preference: Arc<RwLock<Option<ShowPreference>>>,
+}
+
+/// What everyone at the table can see about a seat in the current game
+///
+/// See [`Lobby::seat_summaries`](crate::lobby::Lobby::seat_summaries).
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct SeatSummary {
+    pub name: Option<String>,
+    pub currency: Currency,
+    pub total_bet: Currency,
+    pub state: PlayerState,
+    pub is_dealer: bool,
+    pub is_sb: bool,
+    pub is_bb: bool,
+    pub is_current_turn: bool,
 }
 
 impl Seat {
     pub fn new(behavior: Box<dyn PlayerBehavior + Send + Sync>) -> Self {
         Self {
             inner: Arc::new(RwLock::new(behavior)),
+            name: None,
+            show_preference: Arc::default(),
+        }
+    }
+
+    pub fn named(behavior: BehaveBox, name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Self::new(behavior)
         }
     }
 
+    /// Whether `other` is this seat
+    ///
+    /// Clones of a seat share the player behavior and are the same seat. Seats that were
+    /// created separately are different, even if their players look the same.
+    #[inline]
+    pub fn same_seat(&self, other: &Seat) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Whether this seat shows losing hands at the showdown
+    ///
+    /// Unless it was set with [`set_show_preference`](Self::set_show_preference), the
+    /// preference of the player behavior is used.
+    pub fn show_preference(&self) -> ShowPreference {
+        let preference = *self
+            .show_preference
+            .read()
+            .expect("could not access show preference of lobby seat");
+        preference.unwrap_or_else(|| self.behavior().show_preference())
+    }
+
+    /// Override the show preference of the player behavior
+    ///
+    /// Like the currency, the override is shared by all clones of this seat, so it also applies
+    /// to the hand that is being played.
+    pub fn set_show_preference(&self, preference: ShowPreference) {
+        *self
+            .show_preference
+            .write()
+            .expect("could not access show preference of lobby seat") = Some(preference);
+    }
+
     #[inline]
     pub fn behavior<'a>(&'a self) -> RwLockReadGuard<'a, BehaveBox> {
         trace!("get seat behavior");
@@ -34,6 +103,23 @@ impl Seat {
             .expect("could not access player behavior of lobby seat")
     }
 
+    /// Whether the player behavior of this seat is a `T`
+    ///
+    /// Frontends can use this to tell humans from computer players.
+    pub fn behavior_is<T: PlayerBehavior + 'static>(&self) -> bool {
+        self.behavior().as_any().is::<T>()
+    }
+
+    /// Call `f` with the player behavior of this seat, if it is a `T`
+    ///
+    /// The behavior is locked for reading while `f` runs.
+    pub fn with_behavior<T: PlayerBehavior + 'static, R>(
+        &self,
+        f: impl FnOnce(&T) -> R,
+    ) -> Option<R> {
+        self.behavior().as_any().downcast_ref::<T>().map(f)
+    }
+
     pub fn currency(&self) -> Currency {
         *self.behavior().currency()
     }
@@ -45,8 +131,46 @@ impl Seat {
     pub fn set_currency(&self, cu: Currency) {
         self.behavior_mut().set_currency(cu);
     }
+
+    /// Add to the currency of this seat
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::CurrencyOverflow`] if the new amount would not fit, the currency is
+    /// left untouched then.
+    pub fn add_currency(&self, cu: Currency) -> Result<()> {
+        let mut behavior = self.behavior_mut();
+        let new = behavior.currency().try_add(cu)?;
+        behavior.set_currency(new);
+        Ok(())
+    }
+
+    /// Take currency from this seat
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoksError::InsufficientFunds`] if the seat does not have `cu`, the currency is
+    /// left untouched then.
+    pub fn withdraw_currency(&self, cu: Currency) -> Result<()> {
+        let mut behavior = self.behavior_mut();
+        let available = *behavior.currency();
+        let new = available
+            .checked_sub(cu)
+            .ok_or_else(|| PoksError::insufficient_funds(cu, available))?;
+        behavior.set_currency(new);
+        Ok(())
+    }
 }
 
+/// Seats are equal if they are the [same seat](Seat::same_seat)
+impl PartialEq for Seat {
+    fn eq(&self, other: &Self) -> bool {
+        self.same_seat(other)
+    }
+}
+
+impl Eq for Seat {}
+
 impl From<BehaveBox> for Seat {
     fn from(value: BehaveBox) -> Self {
         Self::new(value)
@@ -55,3 +179,54 @@ impl From<BehaveBox> for Seat {
 
 unsafe impl Send for Seat {}
 unsafe impl Sync for Seat {}
+
+#[cfg(test)]
+mod test {
+    use crate::CU;
+    use crate::lobby::Seat;
+    use crate::players::{PlayerBehavior, PlayerCPU, PlayerLocal, ShowPreference, Strategy};
+
+    #[test]
+    fn test_same_seat() {
+        let a = Seat::new(Box::new(PlayerCPU::default()));
+        let b = Seat::new(Box::new(PlayerCPU::default()));
+        a.set_currency(CU!(5000));
+        b.set_currency(CU!(5000));
+
+        assert_ne!(a, b);
+        assert!(!a.same_seat(&b));
+        let clone = a.clone();
+        assert_eq!(a, clone);
+        // changes through one handle are seen through the other
+        clone.set_currency(CU!(10));
+        assert_eq!(a.currency(), CU!(10));
+        assert_eq!([&a, &b].iter().position(|s| **s == b), Some(1));
+    }
+
+    #[test]
+    fn test_show_preference_is_shared() {
+        let seat = Seat::new(Box::new(PlayerCPU::default()));
+        let clone = seat.clone();
+        assert_eq!(clone.show_preference(), ShowPreference::AutoMuck);
+
+        seat.set_show_preference(ShowPreference::AlwaysShow);
+        assert_eq!(clone.show_preference(), ShowPreference::AlwaysShow);
+    }
+
+    #[test]
+    fn test_behavior_is() {
+        let human = Seat::new(Box::new(PlayerLocal::new()));
+        let cpu = Seat::new(Box::new(PlayerCPU::new(Strategy::LooseAggressive)));
+
+        assert!(human.behavior_is::<PlayerLocal>());
+        assert!(!human.behavior_is::<PlayerCPU>());
+        assert!(cpu.behavior_is::<PlayerCPU>());
+        assert!(!cpu.behavior_is::<PlayerLocal>());
+
+        assert_eq!(
+            cpu.with_behavior(|cpu: &PlayerCPU| cpu.strategy()),
+            Some(Some(Strategy::LooseAggressive))
+        );
+        assert_eq!(human.with_behavior(|cpu: &PlayerCPU| cpu.strategy()), None);
+    }
+}
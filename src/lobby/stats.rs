@@ -0,0 +1,121 @@
+use crate::currency::Currency;
+use crate::game::{Action, Game, GameEvent, Phase, PlayerID};
+use crate::players::PlayerState;
+
+/// What a seat did over all hands of a lobby
+///
+/// See [`Lobby::stats`](crate::lobby::Lobby::stats). Hands are counted once they are finished.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerStats {
+    /// Hands the seat was dealt into
+    pub hands_played: u64,
+    /// Hands in which the seat won at least a share of a pot
+    pub hands_won: u64,
+    /// Hands in which the seat called or raised before the flop, posting a blind does not count
+    pub vpip_hands: u64,
+    /// Everything the seat put into pots, blinds included
+    pub total_wagered: Currency,
+    /// Everything the seat won from pots, after the rake
+    pub total_won: Currency,
+}
+
+impl PlayerStats {
+    /// How much the seat won or lost over all hands, in cents
+    #[must_use]
+    pub fn net(&self) -> i128 {
+        i128::from(*self.total_won) - i128::from(*self.total_wagered)
+    }
+
+    /// The share of the played hands in which the seat voluntarily put chips into the pot
+    ///
+    /// Returns `0.0` if no hands were played.
+    #[must_use]
+    pub fn vpip(&self) -> f64 {
+        if self.hands_played == 0 {
+            return 0.0;
+        }
+        self.vpip_hands as f64 / self.hands_played as f64
+    }
+
+    /// Add the finished `game` to the stats of the seat `pid`
+    pub(crate) fn record_hand(&mut self, game: &Game, pid: PlayerID) {
+        if game.players()[pid].state() == PlayerState::Lost {
+            return;
+        }
+        self.hands_played += 1;
+        self.total_wagered += game.contributed_by(pid);
+
+        let mut phase = Phase::Preflop;
+        let mut vpip = false;
+        let mut won = Currency::ZERO;
+        for event in game.events() {
+            match event {
+                GameEvent::PhaseChanged(p) => phase = *p,
+                GameEvent::Acted(actor, Action::Call(_) | Action::Raise(_) | Action::AllIn(_))
+                    if *actor == pid && phase == Phase::Preflop =>
+                {
+                    vpip = true
+                }
+                GameEvent::Winner(winner) => {
+                    won += winner
+                        .shares()
+                        .iter()
+                        .filter(|(winner, _)| *winner == pid)
+                        .map(|(_, amount)| *amount)
+                        .sum::<Currency>()
+                }
+                _ => (),
+            }
+        }
+        self.vpip_hands += u64::from(vpip);
+        if won > Currency::ZERO {
+            self.hands_won += 1;
+            self.total_won += won;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CU;
+    use crate::errors::PoksError;
+    use crate::lobby::Lobby;
+    use crate::players::Strategy;
+
+    #[test]
+    fn test_stats_net_sums_to_zero() {
+        let mut builder = Lobby::builder();
+        for (name, strategy) in [
+            ("A", Strategy::Random),
+            ("B", Strategy::LooseAggressive),
+            ("C", Strategy::TightPassive),
+            ("D", Strategy::Random),
+        ] {
+            builder.cpu_named(name, CU!(500), strategy).unwrap();
+        }
+        let mut lobby = builder.build().unwrap();
+        for _ in 0..50 {
+            while !lobby.game.is_finished() {
+                lobby.tick_game().unwrap();
+            }
+            match lobby.start_new_game() {
+                Err(PoksError::SessionOver { .. }) => break,
+                res => res.unwrap(),
+            }
+        }
+
+        let stats: Vec<_> = (0..4).map(|pid| lobby.stats(pid).unwrap()).collect();
+        assert_eq!(lobby.stats(4), None);
+        assert_eq!(stats.iter().map(|s| s.net()).sum::<i128>(), 0);
+        for (pid, s) in stats.iter().enumerate() {
+            assert!(s.hands_played > 0);
+            assert!(s.hands_won <= s.hands_played);
+            assert!(s.vpip_hands <= s.hands_played);
+            let stack = *lobby.players()[pid].currency() as i128;
+            assert_eq!(stack, i128::from(*CU!(500)) + s.net());
+        }
+        let won: u64 = stats.iter().map(|s| s.hands_won).sum();
+        assert!(won >= lobby.games_played() - 1);
+    }
+}
@@ -1,38 +1,290 @@
+use std::fmt::Debug;
+
 use rand::prelude::*;
 
 use crate::{
-    CU, Result,
-    game::{Action, Game},
+    Result,
+    currency::Currency,
+    game::{Action, Cards, Game, GameState, Phase},
     player_impl,
     players::PlayerBasicFields,
 };
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+/// Play styles of the [`PlayerCPU`]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Strategy {
+    /// Acts completely at random
+    #[default]
+    Random,
+    /// Folds a lot, rarely raises and never goes all in
+    TightPassive,
+    /// Rarely folds and raises often
+    LooseAggressive,
+}
+
+/// Decides what a [`PlayerCPU`] does when it is their turn
+///
+/// `me` holds the hand and currency of the deciding player. They can not be read from `game`, as
+/// the seat of the player is locked while they act. Decisions that are not allowed right now
+/// are fixed by the [`PlayerCPU`]: raises above the betting limit are made as big as allowed,
+/// raises become calls when raising is disallowed, and raises that the player can not afford
+/// become folds.
+pub trait CpuStrategy: Debug + Send + Sync {
+    fn decide(&mut self, game: &Game, me: &PlayerBasicFields) -> Action;
+
+    /// The play style of this strategy, if it is one of the [`Strategy`] variants
+    #[inline]
+    fn style(&self) -> Option<Strategy> {
+        None
+    }
+}
+
+/// Picks actions at random, weighted by a [`Strategy`]
+///
+/// The random numbers come from [`Game::player_rng`], so seeded games are played the same way
+/// every time.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct RandomStrategy {
+    style: Strategy,
+}
+
+/// Only plays hands that are strong before the flop, and folds the rest to any bet
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct TightStrategy;
+
+#[derive(Debug)]
 pub struct PlayerCPU {
     base: PlayerBasicFields,
+    strategy: Box<dyn CpuStrategy>,
+}
+
+impl Strategy {
+    /// Upper bounds of a random discriminant in `0..=100` for folding, calling, raising a
+    /// little and raising a lot. Everything above goes all in.
+    const fn thresholds(&self) -> [u8; 4] {
+        match self {
+            Strategy::Random => [10, 70, 99, 100],
+            Strategy::TightPassive => [30, 95, 101, 101],
+            Strategy::LooseAggressive => [5, 45, 90, 100],
+        }
+    }
+}
+
+impl RandomStrategy {
+    pub fn new(style: Strategy) -> Self {
+        Self { style }
+    }
+}
+
+impl CpuStrategy for RandomStrategy {
+    fn decide(&mut self, game: &Game, me: &PlayerBasicFields) -> Action {
+        let mut rng = game.player_rng(game.turn());
+        let disc: u8 = rng.gen_range(0..=100);
+        let [fold, call, raise, big_raise] = self.style.thresholds();
+        let call_action = game.action_call();
+        let to_call = call_action.chips_required(game, game.turn());
+        if disc < fold {
+            Action::Fold
+        } else if disc < call {
+            call_action
+        } else if disc < raise {
+            Action::Raise(to_call + game.min_raise_amount())
+        } else if disc < big_raise {
+            Action::Raise(to_call + game.min_raise_amount() * 10)
+        } else {
+            Action::AllIn(me.currency)
+        }
+    }
+
+    fn style(&self) -> Option<Strategy> {
+        Some(self.style)
+    }
+}
+
+impl TightStrategy {
+    /// Hands at least this strong are raised before the flop
+    const RAISE_STRENGTH: u8 = 40;
+    /// Hands at least this strong are played
+    const PLAY_STRENGTH: u8 = 28;
+
+    /// A rough strength of a starting hand, higher is better
+    ///
+    /// Pairs are worth the most, then high cards. Suited and connected cards get a bonus, gaps
+    /// between the ranks a malus. Pocket aces are worth 56, seven-two offsuit 4.
+    #[must_use]
+    pub fn hand_strength(hand: &Cards<2>) -> u8 {
+        let value = |c: &poker::Card| {
+            "23456789TJQKA"
+                .find(c.rank().as_char())
+                .expect("every rank has a character") as u8
+                + 2
+        };
+        let (high, low) = {
+            let (a, b) = (value(&hand[0]), value(&hand[1]));
+            (a.max(b), a.min(b))
+        };
+        if high == low {
+            return 28 + 2 * high;
+        }
+        let mut strength = 2 * high + low;
+        if hand[0].suit() == hand[1].suit() {
+            strength += 4;
+        }
+        let gap = high - low - 1;
+        strength.saturating_sub(3 * gap.min(4))
+    }
+}
+
+impl CpuStrategy for TightStrategy {
+    fn decide(&mut self, game: &Game, me: &PlayerBasicFields) -> Action {
+        let call_action = game.action_call();
+        let to_call = call_action.chips_required(game, game.turn());
+        let strength = me.hand.as_ref().map_or(0, Self::hand_strength);
+        if strength >= Self::RAISE_STRENGTH && game.phase() == Phase::Preflop {
+            Action::Raise(to_call + game.min_raise_amount() * 3)
+        } else if strength >= Self::PLAY_STRENGTH || to_call == Currency::ZERO {
+            call_action
+        } else {
+            Action::Fold
+        }
+    }
+}
+
+impl Default for PlayerCPU {
+    fn default() -> Self {
+        Self::new(Strategy::default())
+    }
+}
+
+impl PlayerCPU {
+    pub fn new(strategy: Strategy) -> Self {
+        Self::with_strategy(Box::new(RandomStrategy::new(strategy)))
+    }
+
+    /// Create a CPU player that decides with the given strategy
+    pub fn with_strategy(strategy: Box<dyn CpuStrategy>) -> Self {
+        Self {
+            base: PlayerBasicFields::default(),
+            strategy,
+        }
+    }
 }
 
 player_impl!(
     PlayerCPU,
     base,
     fn act(&mut self, game: &Game) -> Result<Option<Action>> {
-        let mut rng = rand::rngs::OsRng;
-        let disc: u8 = rng.gen_range(0..=100);
-        let mut a = match disc {
-            0..10 => Action::Fold,
-            10..70 => game.action_call(),
-            70..99 => Action::Raise(CU!(10)),
-            99 => Action::Raise(CU!(100)),
-            100 => Action::AllIn(*self.currency()),
-            _ => unreachable!(),
-        };
+        let call_action = game.action_call();
+        let mut a = self.strategy.decide(game, &self.base);
 
-        if let Action::Raise(bet) = a {
-            if bet >= *self.currency() {
-                a = Action::Fold;
+        if let Some(max) = game.max_raise_amount() {
+            // stay within the betting limit of the game
+            let max_raise = call_action.chips_required(game, game.turn()) + max;
+            match a {
+                Action::Raise(bet) | Action::AllIn(bet) if bet > max_raise => {
+                    a = Action::Raise(max_raise)
+                }
+                _ => (),
             }
         }
 
+        match a {
+            Action::Raise(_) | Action::AllIn(_) if game.state() == GameState::RaiseDisallowed => {
+                a = call_action
+            }
+            Action::Raise(bet) if bet >= *self.currency() => a = Action::Fold,
+            _ => (),
+        }
+        match a {
+            // the call takes everything that is left
+            Action::Call(to_call) if to_call >= *self.currency() => {
+                a = Action::AllIn(*self.currency())
+            }
+            _ => (),
+        }
+
         Ok(Some(a))
+    },
+    fn strategy(&self) -> Option<Strategy> {
+        self.strategy.style()
     }
 );
+
+#[cfg(test)]
+mod test {
+    use crate::CU;
+    use crate::game::{Action, Game, test_seats};
+    use crate::players::{CpuStrategy, PlayerBasicFields, RandomStrategy, Strategy, TightStrategy};
+
+    fn fields(hand: &str) -> PlayerBasicFields {
+        let cards: Vec<poker::Card> = hand
+            .split_whitespace()
+            .map(|c| c.parse().unwrap())
+            .collect();
+        PlayerBasicFields {
+            hand: Some([cards[0], cards[1]]),
+            currency: CU!(5000),
+        }
+    }
+
+    #[test]
+    fn test_hand_strength() {
+        let strength = |hand: &str| TightStrategy::hand_strength(&fields(hand).hand.unwrap());
+        assert_eq!(strength("As Ah"), 56);
+        assert_eq!(strength("7h 2c"), 4);
+        assert!(strength("Ks Qs") > strength("Kd Qc"));
+        assert!(strength("2s 2h") > strength("Ah 9c"));
+        assert!(strength("Jh Th") > strength("Jh 4h"));
+    }
+
+    #[test]
+    fn test_tight_strategy() {
+        let seats = test_seats(3, CU!(5000));
+        let mut game = Game::build(&seats, 0).unwrap();
+        game.process_action(Some(Action::Raise(CU!(10)))).unwrap();
+
+        let mut tight = TightStrategy;
+        assert_eq!(tight.decide(&game, &fields("7h 2c")), Action::Fold);
+        assert_eq!(tight.decide(&game, &fields("Jh Th")), game.action_call());
+        assert!(matches!(
+            tight.decide(&game, &fields("As Ad")),
+            Action::Raise(_)
+        ));
+    }
+
+    #[test]
+    fn test_random_strategy_mix() {
+        let seats = test_seats(3, CU!(5000));
+        let mut random = RandomStrategy::new(Strategy::Random);
+        let me = fields("7h 2c");
+        // fold, call, raise, raise a lot, all in
+        let mut counts = [0u32; 5];
+        let samples = 10_000u16;
+        for i in 0..samples {
+            let mut seed = [0; 32];
+            seed[..2].copy_from_slice(&i.to_le_bytes());
+            let game = Game::buid_with_seed(&seats, 0, seed).unwrap();
+            let min_raise = game.min_raise_amount();
+            let kind = match random.decide(&game, &me) {
+                Action::Fold => 0,
+                Action::Call(_) => 1,
+                Action::Raise(amount) if amount <= CU!(1) + min_raise => 2,
+                Action::Raise(_) => 3,
+                Action::AllIn(_) => 4,
+                Action::Check => panic!("the player faces the big blind"),
+            };
+            counts[kind] += 1;
+        }
+
+        // the thresholds of the random style, out of 101 possible discriminants
+        let expected = [10.0, 60.0, 29.0, 1.0, 1.0].map(|n: f64| n / 101.0);
+        for (count, expected) in counts.iter().zip(expected) {
+            let share = f64::from(*count) / f64::from(samples);
+            assert!(
+                (share - expected).abs() < 0.015,
+                "expected a share of {expected}, got {share} ({counts:?})"
+            );
+            assert!(*count > 0);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, RwLock};
 
 use crate::Result;
@@ -5,6 +6,7 @@ use crate::game::{Action, Game};
 use crate::{player_impl, players::PlayerBasicFields};
 
 pub type ActionAccessor = Arc<RwLock<Option<Action>>>;
+pub type ActionSender = Sender<Action>;
 
 #[derive(Debug, Clone, Default)]
 pub struct PlayerLocal {
@@ -48,3 +50,93 @@ player_impl!(
         Ok(self.take_next_action())
     }
 );
+
+/// A local player whose actions are sent through a channel
+///
+/// Unlike with [`PlayerLocal`], actions that are sent before the player has to act are queued
+/// instead of replacing each other. Every time the player has to act, the oldest queued action
+/// is taken, [`None`] means that nothing is queued.
+#[derive(Debug)]
+pub struct PlayerChannel {
+    pub base: PlayerBasicFields,
+    sender: ActionSender,
+    receiver: Receiver<Action>,
+}
+
+impl PlayerChannel {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            base: PlayerBasicFields::default(),
+            sender,
+            receiver,
+        }
+    }
+
+    /// A sender for the actions of this player, it can be cloned and moved to other threads
+    pub fn action_sender(&self) -> ActionSender {
+        self.sender.clone()
+    }
+}
+
+impl Default for PlayerChannel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+player_impl!(
+    PlayerChannel,
+    base,
+    fn act(&mut self, _game: &Game) -> Result<Option<Action>> {
+        // this player holds a sender itself, so the channel is never disconnected
+        Ok(self.receiver.try_recv().ok())
+    }
+);
+
+#[cfg(test)]
+mod test {
+    use crate::CU;
+    use crate::game::{Action, Phase};
+    use crate::lobby::Lobby;
+    use crate::players::PlayerChannel;
+
+    #[test]
+    fn test_channel_keeps_queued_actions() {
+        let players = [PlayerChannel::new(), PlayerChannel::new()];
+        let senders: Vec<_> = players.iter().map(PlayerChannel::action_sender).collect();
+        let mut builder = Lobby::builder();
+        for player in players {
+            builder.add_player(Box::new(player)).unwrap();
+        }
+        builder.with_starting_stack(CU!(100));
+        let mut lobby = builder.build().unwrap();
+        // heads up, the dealer acts first before the flop and last after it
+        let dealer = lobby.game.turn();
+        let other = 1 - dealer;
+
+        // both actions of the dealer are sent before they act the first time
+        senders[dealer].send(Action::Call(CU!(0, 50))).unwrap();
+        senders[dealer].send(Action::Check).unwrap();
+        senders[other].send(Action::Check).unwrap();
+        for _ in 0..2 {
+            lobby.tick_game().unwrap();
+        }
+        assert_eq!(lobby.game.phase(), Phase::Flop);
+        senders[other].send(Action::Check).unwrap();
+        for _ in 0..2 {
+            lobby.tick_game().unwrap();
+        }
+
+        assert_eq!(lobby.game.phase(), Phase::Turn);
+        assert_eq!(
+            lobby.game.action_history(),
+            [
+                (dealer, Action::Call(CU!(0, 50))),
+                (other, Action::Check),
+                (other, Action::Check),
+                (dealer, Action::Check),
+            ]
+        );
+    }
+}
@@ -1,16 +1,18 @@
 pub mod cpu;
 pub mod local;
 
-pub use cpu::PlayerCPU;
-pub use local::PlayerLocal;
+pub use cpu::{CpuStrategy, PlayerCPU, RandomStrategy, Strategy, TightStrategy};
+pub use local::{PlayerChannel, PlayerLocal};
 
+use std::any::Any;
 use std::fmt::Debug;
 
 use crate::Result;
 use crate::currency::Currency;
-use crate::game::{Action, Cards, Game};
+use crate::game::{Action, Cards, Game, PlayerID};
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PlayerState {
     #[default]
     Playing,
@@ -20,12 +22,30 @@ pub enum PlayerState {
     Lost,
 }
 
+/// Whether a player shows their hand at the showdown
+///
+/// Winning hands are always shown, as they have to be to claim the pot. The preference only
+/// decides about the hands that lost.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShowPreference {
+    /// Losing hands are mucked without showing them
+    #[default]
+    AutoMuck,
+    /// The hand is shown, even if it lost
+    AlwaysShow,
+}
+
 pub trait PlayerBehavior: Debug {
     fn hand(&self) -> &Option<Cards<2>>;
     fn hand_mut(&mut self) -> &mut Option<Cards<2>>;
     fn currency(&self) -> &Currency;
     fn currency_mut(&mut self) -> &mut Currency;
     fn act(&mut self, game: &Game) -> Result<Option<Action>>;
+    /// This player as [`Any`], so the concrete type can be recovered
+    ///
+    /// See [`Seat::behavior_is`](crate::lobby::Seat::behavior_is).
+    fn as_any(&self) -> &dyn Any;
 
     #[inline]
     fn set_hand(&mut self, new: Cards<2>) {
@@ -35,6 +55,33 @@ pub trait PlayerBehavior: Debug {
     fn set_currency(&mut self, new: Currency) {
         *self.currency_mut() = new;
     }
+    /// The play style, if this player is controlled by the computer
+    #[inline]
+    fn strategy(&self) -> Option<Strategy> {
+        None
+    }
+    /// Called by the [`Lobby`](crate::lobby::Lobby) when a new hand was dealt
+    ///
+    /// `my_id` is the seat of this player. Like in [`act`](Self::act), the seat of this player
+    /// is locked, so the hand and currency have to be read from `self` instead of `game`.
+    #[inline]
+    fn on_hand_start(&mut self, _game: &Game, _my_id: PlayerID) {}
+    /// Called by the [`Lobby`](crate::lobby::Lobby) when a hand this player was dealt into is
+    /// finished
+    ///
+    /// `won` tells whether the player has more chips than before the hand, `net` is by how much
+    /// their stack went up or down. Like in [`act`](Self::act), the seat of this player is
+    /// locked.
+    #[inline]
+    fn on_hand_result(&mut self, _game: &Game, _won: bool, _net: Currency) {}
+    /// Whether this player shows losing hands at the showdown
+    ///
+    /// A [`Seat`](crate::lobby::Seat) can override this with
+    /// [`Seat::set_show_preference`](crate::lobby::Seat::set_show_preference).
+    #[inline]
+    fn show_preference(&self) -> ShowPreference {
+        ShowPreference::default()
+    }
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Default)]
@@ -60,6 +107,9 @@ macro_rules! player_impl {
             fn currency_mut(&mut self) -> &mut $crate::currency::Currency {
                 &mut self.$base_field.currency
             }
+            fn as_any(&self) -> &dyn ::std::any::Any {
+                self
+            }
             $($extra)+
         }
         #[automatically_derived]
@@ -1,31 +1,52 @@
 use ntest::timeout;
-use poksen::{CU, lobby::Lobby, players::PlayerCPU};
+use poksen::{
+    CU,
+    game::Action,
+    lobby::{GameRunner, Lobby},
+    players::PlayerLocal,
+};
 
 fn get_world() -> Lobby {
     let mut wb = Lobby::builder();
-    for _ in 0..8 {
-        wb.add_player(Box::new(PlayerCPU::default())).unwrap();
-    }
-    for player in wb.players.iter_mut() {
-        player.set_currency(CU!(5000));
-    }
+    wb.add_cpu_players(8, CU!(5000)).unwrap();
     wb.build().unwrap()
 }
 
 #[test]
 #[timeout(300)]
 fn test_play_50_games_cpu() {
-    let mut w = get_world();
-    for _gi in 0..50 {
-        w.start_new_game().unwrap();
-        while !w.game.is_finished() {
-            w.tick_game().unwrap();
-            let last_action = w.action_log().iter().last().unwrap();
-            if let Some(pid) = last_action.0 {
-                println!("Player {pid}: {}", last_action.1)
-            } else {
-                println!("{}", last_action.1)
-            }
+    let mut runner = GameRunner::new(get_world());
+    let chips = runner.lobby().total_chips();
+    // the session ends early once a player has all the chips
+    let winners = runner.run_session(50).unwrap();
+    assert!(!winners.is_empty());
+    assert_eq!(runner.lobby().total_chips(), chips);
+}
+
+#[test]
+#[timeout(300)]
+fn test_winner_is_last_action_log_entry() {
+    let mut wb = Lobby::builder();
+    let mut accessors = Vec::new();
+    for _ in 0..2 {
+        let player = Box::new(PlayerLocal::new());
+        accessors.push(player.action_field_reference());
+        wb.add_player(player).unwrap();
+    }
+    for player in wb.players.iter_mut() {
+        player.set_currency(CU!(5000));
+    }
+    let mut w = wb.build().unwrap();
+
+    while !w.game.is_finished() {
+        for accessor in &accessors {
+            PlayerLocal::set_action(accessor, Action::Fold);
         }
+        w.tick_game().unwrap();
     }
+
+    let winner = w.game.winner().unwrap();
+    // the action log iterates from the newest to the oldest entry
+    let last_entry = w.action_log().iter().next().unwrap();
+    assert_eq!(*last_entry, (None, winner.to_string()));
 }